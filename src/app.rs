@@ -1,84 +1,1868 @@
-use crate::ui::ui::{draw_docs_modal, draw_entry_line, draw_help_modal, draw_ui, View, UI};
+use crate::ui::ui::{
+    draw_bookmarks, draw_docs_modal, draw_entry_line, draw_filters_modal, draw_fuzzy_finder,
+    draw_global_search, draw_help_modal, draw_match_builder, draw_patterns, draw_ui, CurrentLine,
+    View, UI,
+};
 use crate::{
     core::{
+        bookmarks::{find_bookmark, insert_bookmark, load_bookmarks, save_bookmarks, Bookmark},
+        bundle::{create_bundle, BundleReport},
+        clipboard::{format_yank_lines, yank_to_clipboard, ClipboardBackend},
+        command::{complete_line, get_completions, Command, CommandCompletion},
         config::Config,
+        dashboard::{load_dashboard, Dashboard, DashboardLink},
+        dedupe::{find_duplicate_runs, get_hidden_lines, DedupeMode, DuplicateRun},
         error::{Result, RounalError},
+        export::{cycle_export_extension, default_export_path, export_logs},
+        filter::{is_unit_visible, UnitFilter},
+        fuzzy::FuzzyFinder,
+        histogram::{Histogram, HISTOGRAM_BUCKETS},
         input_handler::handle_key_events,
-        journal::{get_journal_logs, JournalLogMap, SharedJournalLogs},
-        system::{get_system_services, ServiceUnitFiles, ServiceUnits},
+        invocation::{
+            get_visible_lines, group_invocations, index_invocations, InvocationGroup,
+            InvocationIndex,
+        },
+        journal::{
+            get_journal_logs, get_journal_sources, get_recent_logs, is_journal_source,
+            search_journal, JournalLog, JournalLogMap, Preview, SharedJournalLogs, SharedPreview,
+            TimeWindow, ENTIRE_JOURNAL,
+        },
+        jump::{find_nearest_entry, parse_jump_time},
+        marks::{load_marks, save_marks, ServiceMarks},
+        matches::{toggle_match, JournalMatch, MatchBuilder},
+        motion::{Motion, ShownPriority},
+        patterns::PatternView,
+        search::{GlobalSearch, SearchRow},
+        sort::{SortList, UnitSort},
+        system::{
+            get_system_services, run_unit_action, ServiceUnitFiles, ServiceUnits, UnitAction,
+        },
+        tabs::LogTab,
     },
     ui::styles::Styler,
+    util::map_to_priority_str,
 };
+use chrono::{DateTime, Local};
 use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
-use log::info;
+use log::{error, info};
 use ratatui::{
     backend::{Backend, CrosstermBackend},
     Terminal,
 };
 use std::{
+    collections::{HashMap, HashSet},
     io::stdout,
     sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
+use tokio::task::JoinHandle;
+
+const PREVIEW_DELAY: Duration = Duration::from_millis(200);
+const PREVIEW_RETRY: Duration = Duration::from_secs(5);
+const JUMP_MARGIN: chrono::Duration = chrono::Duration::hours(1);
+const JUMP_AFTER_LIMITED: chrono::Duration = chrono::Duration::minutes(1);
+const PREVIEW_LINES: usize = 200;
+
+// TODO
+#[derive(Debug, PartialEq)]
+pub enum Events {
+    Quit,
+    GetLogs,
+    GetHelp,
+    GetLineInModal,
+    Search,
+    Docs,
+    GlobalSearch,
+    RunGlobalSearch,
+    FuzzyFinder,
+    Filters,
+    GetFieldValues,
+    Dashboard,
+    Export,
+    OverwriteExport,
+    Yank,
+    Bundle,
+    Command,
+    UnitAction,
+    Theme,
+    SaveBookmarks,
+    SaveMarks,
+}
+
+#[derive(Debug)]
+pub struct App {
+    pub ui: UI,
+    pub config: Config,
+    pub clipboard: ClipboardBackend,
+    pub is_running: bool,
+    pub logs: Option<SharedJournalLogs>,
+    pub services: Option<(Vec<ServiceUnits>, Vec<ServiceUnitFiles>)>,
+    pub journal_sources: Vec<ServiceUnits>,
+    pub dashboard: Option<Dashboard>,
+    pub dashboard_loading: Option<JoinHandle<Dashboard>>,
+    pub bundle_unit: Option<String>,
+    pub bundle_loading: Option<JoinHandle<Result<BundleReport>>>,
+    pub pending_unit_action: Option<(UnitAction, String)>,
+    // sent when the confirm prompt is answered with y
+    pub confirm_event: Option<Events>,
+    pub selected_service: Option<String>,
+    pub selected_units: Vec<String>,
+    pub marked_units: Vec<String>,
+    pub journal_matches: Vec<JournalMatch>,
+    pub log_window: TimeWindow,
+    pub match_builder: Option<MatchBuilder>,
+    pub patterns: Option<PatternView>,
+    pub invocations: InvocationIndex,
+    pub folded_invocations: HashSet<String>,
+    pub invocation_groups: HashMap<u8, Vec<InvocationGroup>>,
+    pub marks: HashMap<String, ServiceMarks>,
+    pub are_marks_saved: bool,
+    pub bookmarks: Vec<Bookmark>,
+    pub annotating: Option<Bookmark>,
+    pub are_bookmarks_saved: bool,
+    // collapsed runs of each priority for the dedupe mode
+    pub duplicate_runs: HashMap<u8, Vec<DuplicateRun>>,
+    pub expanded_duplicates: HashSet<String>,
+    pub global_search: Option<GlobalSearch>,
+    pub jump_to_cursor: Option<String>,
+    pub jump_to_realtime: Option<u64>,
+    // the window of the fetch for a jump, the next fetches use the log window
+    pub jump_window: Option<TimeWindow>,
+    pub fuzzy_finder: Option<FuzzyFinder>,
+    pub unit_filters: Vec<UnitFilter>,
+    pub unit_sort: UnitSort,
+    pub file_sort: UnitSort,
+    pub preview: SharedPreview,
+    // cleared by the fetch when it fails, so the unit is requested again
+    pub preview_requested: Arc<Mutex<Option<String>>>,
+    pub preview_candidate: Option<(String, Instant)>,
+    pub tabs: Vec<LogTab>,
+    pub active_tab: Option<usize>,
+}
+
+impl App {
+    pub fn new(config: Config) -> Self {
+        let (unit_filters, invalid_filters) = UnitFilter::parse_all(&config.options.unit_filter);
+        let clipboard = ClipboardBackend::from_options(&config.options);
+        info!("Clipboard: {}", clipboard.name());
+
+        let mut ui = UI::new();
+        ui.is_grouping_invocations = config.options.group_invocations;
+        if !invalid_filters.is_empty() {
+            ui.notify(
+                format!("Invalid unit filter: {}", invalid_filters.join(", ")),
+                true,
+            );
+        }
+
+        Self {
+            ui,
+            config,
+            clipboard,
+            is_running: true,
+            logs: None,
+            services: None,
+            journal_sources: get_journal_sources(),
+            dashboard: None,
+            dashboard_loading: None,
+            bundle_unit: None,
+            bundle_loading: None,
+            pending_unit_action: None,
+            confirm_event: None,
+            selected_service: None,
+            selected_units: vec![],
+            marked_units: vec![],
+            journal_matches: vec![],
+            log_window: TimeWindow::default(),
+            match_builder: None,
+            patterns: None,
+            invocations: InvocationIndex::new(),
+            folded_invocations: HashSet::new(),
+            invocation_groups: HashMap::new(),
+            marks: HashMap::new(),
+            are_marks_saved: true,
+            bookmarks: vec![],
+            annotating: None,
+            are_bookmarks_saved: true,
+            duplicate_runs: HashMap::new(),
+            expanded_duplicates: HashSet::new(),
+            global_search: None,
+            jump_to_cursor: None,
+            jump_to_realtime: None,
+            jump_window: None,
+            fuzzy_finder: None,
+            unit_filters,
+            unit_sort: UnitSort::new(),
+            file_sort: UnitSort::new(),
+            preview: Arc::new(Mutex::new(None)),
+            preview_requested: Arc::new(Mutex::new(None)),
+            preview_candidate: None,
+            tabs: vec![],
+            active_tab: None,
+        }
+    }
+
+    pub fn set_is_running(&mut self, state: bool) {
+        self.is_running = state;
+    }
+
+    pub fn set_services(
+        &mut self,
+        services: (Vec<ServiceUnits>, Vec<ServiceUnitFiles>),
+    ) -> Result<()> {
+        self.services = Some(services);
+        Ok(())
+    }
+
+    pub fn set_logs(&mut self, logs: Arc<Mutex<JournalLogMap>>) {
+        self.logs = Some(logs);
+        self.update_log_indexes();
+    }
+
+    pub fn clear_logs(&mut self) {
+        self.logs = None;
+        self.update_log_indexes();
+    }
+
+    // what is found in the logs once instead of on every frame
+    fn update_log_indexes(&mut self) {
+        self.invocations = self
+            .logs
+            .as_ref()
+            .and_then(|logs_arc| logs_arc.lock().ok().map(|logs| index_invocations(&logs)))
+            .unwrap_or_default();
+        self.update_invocation_groups();
+        self.update_duplicate_runs();
+    }
+
+    // grouped again when the order of the entries changes, like the runs
+    fn update_invocation_groups(&mut self) {
+        self.invocation_groups = self
+            .logs
+            .as_ref()
+            .and_then(|logs_arc| {
+                let logs_map = logs_arc.lock().ok()?;
+                Some(
+                    logs_map
+                        .iter()
+                        .map(|(priority, entries)| (*priority, group_invocations(entries)))
+                        .collect(),
+                )
+            })
+            .unwrap_or_default();
+    }
+
+    fn update_duplicate_runs(&mut self) {
+        let mode = self.ui.dedupe;
+        if mode == DedupeMode::Off {
+            self.duplicate_runs.clear();
+            return;
+        }
+
+        self.duplicate_runs = self
+            .logs
+            .as_ref()
+            .and_then(|logs_arc| {
+                let logs_map = logs_arc.lock().ok()?;
+                Some(
+                    logs_map
+                        .iter()
+                        .map(|(priority, entries)| (*priority, find_duplicate_runs(entries, mode)))
+                        .collect(),
+                )
+            })
+            .unwrap_or_default();
+    }
+
+    // kernel and whole journal stay on top, unaffected by filters and sorting
+    pub fn get_visible_units(&self) -> Vec<&ServiceUnits> {
+        match &self.services {
+            Some((units, _)) => self
+                .journal_sources
+                .iter()
+                .chain(
+                    units
+                        .iter()
+                        .filter(|unit| is_unit_visible(&self.unit_filters, unit)),
+                )
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    pub fn is_merged_view(&self) -> bool {
+        self.selected_units.len() > 1
+    }
+
+    pub fn is_split_layout(&self) -> bool {
+        self.config.options.get_split_direction().is_some()
+    }
+
+    // the unit under the services cursor, also while the logs have focus
+    pub fn get_highlighted_service(&self) -> Option<String> {
+        let line = if self.ui.is_in_logs {
+            self.ui.services_line
+        } else {
+            self.ui.current_line
+        };
+
+        match self.ui.view {
+            View::ServiceUnits => self
+                .get_visible_units()
+                .get(line)
+                .map(|unit| unit.name.clone()),
+            View::ServiceUnitFiles => self
+                .services
+                .as_ref()?
+                .1
+                .get(line)
+                .map(|file| file.name.clone()),
+            View::Dashboard => match &self.dashboard.as_ref()?.get_selected()?.link {
+                DashboardLink::Logs { unit, .. } => Some(unit.clone()),
+                DashboardLink::Units(_) => None,
+            },
+        }
+    }
+
+    pub fn open_dashboard(&mut self) -> Option<Events> {
+        self.ui.set_view(View::Dashboard);
+        Some(Events::Dashboard)
+    }
+
+    // the journal queries take a while, the old dashboard stays until then
+    pub fn load_dashboard(&mut self) {
+        if let Some(handle) = self.dashboard_loading.take() {
+            handle.abort();
+        }
+
+        let units = self
+            .services
+            .as_ref()
+            .map(|(units, _)| units.clone())
+            .unwrap_or_default();
+        let initial_priority = self.config.options.initial_priority;
+        self.dashboard_loading = Some(tokio::spawn(async move {
+            load_dashboard(&units, initial_priority).await
+        }));
+    }
+
+    pub fn is_dashboard_loading(&self) -> bool {
+        self.dashboard_loading.is_some()
+    }
+
+    pub async fn poll_dashboard(&mut self) {
+        if !self
+            .dashboard_loading
+            .as_ref()
+            .is_some_and(|handle| handle.is_finished())
+        {
+            return;
+        }
+
+        let Some(handle) = self.dashboard_loading.take() else {
+            return;
+        };
+
+        let mut dashboard = match handle.await {
+            Ok(dashboard) => dashboard,
+            Err(e) => {
+                error!("Loading task for the dashboard failed: {}", e);
+                return;
+            }
+        };
+
+        // a refresh keeps the cursor where it was
+        if let Some(previous) = &self.dashboard {
+            dashboard.current_line = previous
+                .current_line
+                .min(dashboard.get_entries().count().saturating_sub(1));
+        }
+        self.dashboard = Some(dashboard);
+    }
+
+    pub fn open_dashboard_link(&mut self) -> Option<Events> {
+        let link = self.dashboard.as_ref()?.get_selected()?.link.clone();
+
+        match link {
+            DashboardLink::Logs { unit, priority } => {
+                info!("OPENING FROM DASHBOARD {} {}", unit, priority);
+                let event = self.open_service(unit);
+                self.ui.set_priority(priority);
+                event
+            }
+            // added to the filters already set, like one picked with F
+            DashboardLink::Units(filter) => {
+                if let Some(filter) = UnitFilter::parse(filter) {
+                    if !self.unit_filters.contains(&filter) {
+                        self.unit_filters.push(filter);
+                    }
+                }
+                self.ui.set_current_line(0);
+                self.ui.set_view(View::ServiceUnits);
+                None
+            }
+        }
+    }
+
+    pub fn open_highlighted_service(&mut self) -> Option<Events> {
+        let service = self.get_highlighted_service()?;
+
+        info!("SELECTED SERVICE NOW {:?}", service);
+        self.open_service(service)
+    }
+
+    pub fn open_service(&mut self, service: String) -> Option<Events> {
+        let tab = LogTab::new(&service, self.get_priority());
+        self.open_tab(tab)
+    }
+
+    // space in the unit list
+    pub fn toggle_mark_highlighted(&mut self) {
+        let Some(service) = self.get_highlighted_service() else {
+            return;
+        };
+
+        // -k and the whole journal do not combine with unit matches
+        if is_journal_source(&service) {
+            return;
+        }
+
+        match self.marked_units.iter().position(|unit| *unit == service) {
+            Some(idx) => {
+                self.marked_units.remove(idx);
+            }
+            None => self.marked_units.push(service),
+        }
+    }
+
+    // M, without marks the highlighted unit is opened on its own
+    pub fn open_marked_units(&mut self) -> Option<Events> {
+        if self.marked_units.len() < 2 {
+            return self.open_highlighted_service();
+        }
+
+        info!("OPENING MERGED VIEW {:?}", self.marked_units);
+        let tab = LogTab::merged(&self.marked_units, self.get_priority());
+        self.marked_units.clear();
+        self.open_tab(tab)
+    }
+
+    // A service that already has a tab is focused there, otherwise it
+    // replaces the focused tab or gets a new one when no tab has focus.
+    fn open_tab(&mut self, tab: LogTab) -> Option<Events> {
+        self.save_active_tab();
+
+        let service = tab.service.clone();
+        self.selected_units = tab.units.clone();
+
+        let idx = match self.tabs.iter().position(|open| open.service == service) {
+            Some(idx) => idx,
+            None => match self.active_tab {
+                Some(idx) => {
+                    self.tabs[idx].cancel_loading();
+                    self.tabs[idx] = tab;
+                    idx
+                }
+                None => {
+                    self.tabs.push(tab);
+                    self.tabs.len() - 1
+                }
+            },
+        };
+
+        // the logs are fetched again right away
+        self.tabs[idx].cancel_loading();
+        self.journal_matches = self.tabs[idx].matches.clone();
+        self.folded_invocations.clear();
+        self.active_tab = Some(idx);
+        self.selected_service = Some(service);
+        self.ui.search_query.clear();
+        self.ui.search_matches.clear();
+        self.ui.open_logs();
+        self.ui.h_scroll = 0;
+        Some(Events::GetLogs)
+    }
+
+    // T, the focused view stays as it is while the logs load
+    pub fn open_highlighted_service_in_tab(&mut self) {
+        let Some(service) = self.get_highlighted_service() else {
+            return;
+        };
+
+        if self.tabs.iter().any(|tab| tab.service == service) {
+            return;
+        }
+
+        let mut tab = LogTab::new(&service, self.get_priority());
+        tab.load_in_background(&self.log_window);
+        self.tabs.push(tab);
+    }
+
+    fn get_priority(&self) -> u8 {
+        self.ui
+            .selected_priority
+            .unwrap_or(self.config.options.initial_priority)
+    }
+
+    fn save_active_tab(&mut self) {
+        self.cancel_pending_jump();
+        let Some(tab) = self.active_tab.and_then(|idx| self.tabs.get_mut(idx)) else {
+            return;
+        };
+
+        tab.logs = self.logs.clone();
+        tab.priority = self
+            .ui
+            .selected_priority
+            .unwrap_or(self.config.options.initial_priority);
+        tab.search_query = self.ui.search_query.clone();
+        tab.matches = self.journal_matches.clone();
+        tab.current_line = if self.ui.is_in_logs {
+            self.ui.current_line
+        } else {
+            self.ui.logs_line
+        };
+        tab.h_scroll = self.ui.h_scroll;
+    }
+
+    fn restore_tab(&mut self, idx: usize) {
+        let Some(tab) = self.tabs.get(idx) else {
+            return;
+        };
+
+        self.active_tab = Some(idx);
+        self.selected_service = Some(tab.service.clone());
+        self.selected_units = tab.units.clone();
+        self.journal_matches = tab.matches.clone();
+        self.logs = tab.logs.clone();
+        self.ui.selected_priority = Some(tab.priority);
+        self.ui.search_query = tab.search_query.clone();
+        self.ui.search_matches.clear();
+        self.ui.h_scroll = tab.h_scroll;
+
+        if self.ui.is_in_logs {
+            self.ui.current_line = tab.current_line;
+        } else {
+            self.ui.logs_line = tab.current_line;
+            self.ui.focus_logs();
+        }
+
+        self.folded_invocations.clear();
+        self.expanded_duplicates.clear();
+        self.ui.visual_anchor = None;
+        self.update_log_indexes();
+    }
+
+    pub fn switch_tab(&mut self, forward: bool) {
+        if self.tabs.is_empty() {
+            return;
+        }
+
+        let len = self.tabs.len();
+        let idx = match (self.active_tab, forward) {
+            (Some(idx), true) => (idx + 1) % len,
+            (Some(idx), false) => (idx + len - 1) % len,
+            (None, true) => 0,
+            (None, false) => len - 1,
+        };
+
+        self.save_active_tab();
+        self.restore_tab(idx);
+    }
+
+    // X, the next tab takes the place of the closed one
+    pub fn close_active_tab(&mut self) {
+        // closing the logs takes the active tab, the neighbour is picked by
+        // the index it had
+        let idx = self.active_tab;
+        self.close_logs();
+
+        if self.tabs.is_empty() {
+            return;
+        }
+
+        let idx = idx.unwrap_or(0).min(self.tabs.len() - 1);
+        self.restore_tab(idx);
+    }
+
+    // c, the other tabs are kept in the tab bar
+    pub fn close_logs(&mut self) {
+        self.cancel_pending_jump();
+        if let Some(idx) = self.active_tab.take() {
+            let mut tab = self.tabs.remove(idx);
+            tab.cancel_loading();
+        }
+
+        self.clear_logs();
+        self.selected_service = None;
+        self.selected_units.clear();
+        self.journal_matches.clear();
+        self.ui.search_query.clear();
+        self.ui.search_matches.clear();
+        self.ui.close_logs();
+        self.ui.selected_priority = Some(self.config.options.initial_priority);
+    }
+
+    pub fn open_match_builder(&mut self) -> Option<Events> {
+        self.match_builder = Some(MatchBuilder::new());
+        self.ui.set_is_showing_match_builder(true);
+        Some(Events::GetFieldValues)
+    }
+
+    // the logs are fetched again only when the matches changed
+    pub fn close_match_builder(&mut self) -> Option<Events> {
+        let mut builder = self.match_builder.take()?;
+        builder.cancel_loading();
+        self.ui.set_is_showing_match_builder(false);
+
+        if !builder.is_changed {
+            return None;
+        }
+        self.ui.set_current_line(0);
+        Some(Events::GetLogs)
+    }
+
+    pub fn toggle_selected_match(&mut self) {
+        let Some(builder) = self.match_builder.as_mut() else {
+            return;
+        };
+
+        if let Some(journal_match) = builder.get_selected() {
+            toggle_match(&mut self.journal_matches, journal_match);
+            builder.is_changed = true;
+        }
+    }
+
+    pub fn clear_matches(&mut self) {
+        if let Some(builder) = self.match_builder.as_mut() {
+            builder.is_changed |= !self.journal_matches.is_empty();
+        }
+        self.journal_matches.clear();
+    }
+
+    pub fn load_match_values(&mut self) {
+        if let Some(builder) = self.match_builder.as_mut() {
+            builder.load_values_in_background(&self.selected_units);
+        }
+    }
+
+    pub async fn poll_match_values(&mut self) {
+        if let Some(builder) = self.match_builder.as_mut() {
+            builder.poll_loading().await;
+        }
+    }
+
+    pub fn is_active_tab_loading(&self) -> bool {
+        self.active_tab
+            .and_then(|idx| self.tabs.get(idx))
+            .is_some_and(|tab| tab.is_loading())
+    }
+
+    pub async fn poll_tabs(&mut self) {
+        let mut is_active_loaded = false;
+        for (idx, tab) in self.tabs.iter_mut().enumerate() {
+            if tab.poll_loading().await && Some(idx) == self.active_tab {
+                self.logs = tab.logs.clone();
+                is_active_loaded = true;
+            }
+        }
+
+        if is_active_loaded {
+            self.update_log_indexes();
+        }
+    }
+
+    // Searching reorders the entries and a merged view interleaves the
+    // invocations of several units, neither groups into anything useful.
+    pub fn get_invocation_groups(&self) -> Option<&[InvocationGroup]> {
+        if !self.ui.is_grouping_invocations
+            || self.is_merged_view()
+            || !self.ui.search_query.is_empty()
+            || self.invocations.is_empty()
+        {
+            return None;
+        }
+
+        self.invocation_groups
+            .get(&self.ui.selected_priority?)
+            .map(Vec::as_slice)
+    }
+
+    pub fn get_histogram(&self) -> Option<Histogram> {
+        let logs_map = self.logs.as_ref()?.lock().ok()?;
+        Histogram::new(logs_map.get(&self.ui.selected_priority?)?)
+    }
+
+    // # starts on the bucket of the entry under the cursor
+    pub fn toggle_histogram_focus(&mut self) {
+        if self.ui.is_in_histogram {
+            self.ui.is_in_histogram = false;
+            return;
+        }
+
+        if self.config.options.histogram_height == 0 {
+            self.ui.notify(
+                "The chart is hidden, histogram_height is 0".to_string(),
+                true,
+            );
+            return;
+        }
+
+        let Some(histogram) = self.get_histogram() else {
+            return;
+        };
+
+        let realtime = self.logs.as_ref().and_then(|logs_arc| {
+            let logs_map = logs_arc.lock().ok()?;
+            logs_map
+                .get(&self.ui.selected_priority?)?
+                .get(self.ui.current_line)
+                .map(|log| log.realtime)
+        });
+
+        self.ui.histogram_bucket = realtime
+            .map(|realtime| histogram.get_bucket_index(realtime))
+            .unwrap_or(0);
+        self.ui.is_in_histogram = true;
+    }
+
+    pub fn move_histogram_bucket(&mut self, forward: bool) {
+        self.ui.histogram_bucket = if forward {
+            (self.ui.histogram_bucket + 1).min(HISTOGRAM_BUCKETS - 1)
+        } else {
+            self.ui.histogram_bucket.saturating_sub(1)
+        };
+    }
+
+    pub fn jump_to_histogram_bucket(&mut self) {
+        let line = self.get_histogram().and_then(|histogram| {
+            let logs_map = self.logs.as_ref()?.lock().ok()?;
+            let entries = logs_map.get(&self.ui.selected_priority?)?;
+            histogram.find_entry(entries, self.ui.histogram_bucket)
+        });
+
+        if let Some(line) = line {
+            self.reveal_log_line(line);
+            self.ui.set_current_line(line);
+            self.ui.is_in_histogram = false;
+        }
+    }
+
+    // templates of the entries at the selected priority
+    pub fn open_patterns(&mut self) {
+        let view = self.logs.as_ref().and_then(|logs_arc| {
+            let logs_map = logs_arc.lock().ok()?;
+            let entries = logs_map.get(&self.ui.selected_priority?)?;
+            Some(PatternView::new(entries))
+        });
+
+        if let Some(view) = view {
+            self.patterns = Some(view);
+            self.ui.set_is_showing_patterns(true);
+        }
+    }
+
+    pub fn close_patterns(&mut self) {
+        self.patterns = None;
+        self.ui.set_is_showing_patterns(false);
+    }
+
+    pub fn open_pattern_entry(&mut self) {
+        let Some(line) = self
+            .patterns
+            .as_ref()
+            .and_then(|view| view.get_selected_line())
+        else {
+            return;
+        };
+
+        self.close_patterns();
+        self.reveal_log_line(line);
+        self.ui.set_current_line(line);
+    }
+
+    // unfolds the invocation of a line the cursor is moved onto
+    fn reveal_log_line(&mut self, line: usize) {
+        if let Some(run) = self
+            .get_collapsed_runs()
+            .into_iter()
+            .find(|run| run.contains(line) && run.first_line != line)
+        {
+            self.expanded_duplicates.insert(run.cursor);
+        }
+
+        if self.folded_invocations.is_empty() {
+            return;
+        }
+
+        if let Some(group) = self
+            .get_invocation_groups()
+            .and_then(|groups| groups.iter().find(|group| group.contains(line)).cloned())
+        {
+            if group.first_line != line {
+                self.folded_invocations.remove(&group.id);
+            }
+        }
+    }
+
+    // Folded invocations are stepped over, only their separator is reachable,
+    // and so are the repeated entries of a collapsed run.
+    fn get_visible_log_lines(&self) -> Option<Vec<usize>> {
+        let hidden = get_hidden_lines(&self.get_collapsed_runs());
+        let groups = if self.folded_invocations.is_empty() {
+            None
+        } else {
+            self.get_invocation_groups()
+        };
+
+        let lines: Vec<usize> = match groups {
+            Some(groups) => get_visible_lines(groups, &self.folded_invocations),
+            None if !hidden.is_empty() => {
+                let logs_map = self.logs.as_ref()?.lock().ok()?;
+                (0..logs_map.get(&self.ui.selected_priority?)?.len()).collect()
+            }
+            None => return None,
+        };
+
+        Some(
+            lines
+                .into_iter()
+                .filter(|line| !hidden.contains(line))
+                .collect(),
+        )
+    }
+
+    // the entries on screen, folded invocations and the repeats of collapsed
+    // runs are left out
+    fn get_shown_logs(&self) -> Vec<JournalLog> {
+        let hidden = get_hidden_lines(&self.get_collapsed_runs());
+        let groups = self.get_invocation_groups().unwrap_or_default();
+        let Some(logs_map) = self.logs.as_ref().and_then(|logs| logs.lock().ok()) else {
+            return vec![];
+        };
+        let Some(entries) = self.ui.selected_priority.and_then(|p| logs_map.get(&p)) else {
+            return vec![];
+        };
+
+        entries
+            .iter()
+            .enumerate()
+            .filter(|(line, _)| {
+                !hidden.contains(line)
+                    && !groups.iter().any(|group| {
+                        group.contains(*line) && self.folded_invocations.contains(&group.id)
+                    })
+            })
+            .map(|(_, log)| log.clone())
+            .collect()
+    }
+
+    pub fn get_logs_len(&self) -> usize {
+        self.logs
+            .as_ref()
+            .and_then(|logs_arc| {
+                let logs_map = logs_arc.lock().ok()?;
+                let priority = self
+                    .ui
+                    .selected_priority
+                    .unwrap_or(self.config.options.initial_priority);
+                logs_map.get(&priority).map(|logs| logs.len())
+            })
+            .unwrap_or(0)
+    }
+
+    pub fn get_services_len(&self) -> usize {
+        match (&self.services, &self.ui.view) {
+            (Some(_), View::ServiceUnits) => self.get_visible_units().len(),
+            (Some((_, files)), _) => files.len(),
+            (None, _) => 0,
+        }
+    }
+
+    // j and k with a count, gg, G, the page motions and H, M, L
+    pub fn move_cursor(&mut self, motion: Motion, count: Option<usize>) {
+        let lines: Vec<usize> = if self.ui.is_in_logs {
+            self.get_visible_log_lines()
+                .unwrap_or_else(|| (0..self.get_logs_len()).collect())
+        } else {
+            (0..self.get_services_len()).collect()
+        };
+
+        let viewport = self.ui.get_viewport();
+        let Some(line) = motion.get_target(&lines, self.ui.current_line, count, viewport) else {
+            return;
+        };
+
+        self.ui
+            .scroll_viewport(motion.get_scroll(count, viewport.height));
+        self.ui.set_current_line(line);
+    }
+
+    // the letter is read after m, the logs of the service keep their marks
+    // while rounal runs, also when the service is closed
+    pub fn set_mark(&mut self, letter: char) -> Option<Events> {
+        let service = self.selected_service.clone()?;
+        let Some(CurrentLine::Log(log)) = self.ui.get_current_line(self) else {
+            return None;
+        };
+        let priority = self
+            .ui
+            .selected_priority
+            .unwrap_or(self.config.options.initial_priority);
+
+        self.marks
+            .entry(service)
+            .or_default()
+            .insert(letter, (priority, log.cursor));
+        self.ui.notify(format!("Mark {} set", letter), false);
+        Some(Events::SaveMarks)
+    }
+
+    pub fn jump_to_mark(&mut self, letter: char) {
+        let Some((priority, cursor)) = self
+            .selected_service
+            .as_ref()
+            .and_then(|service| self.marks.get(service)?.get(&letter).cloned())
+        else {
+            self.ui.notify(format!("Mark {} is not set", letter), true);
+            return;
+        };
+
+        if self.ui.selected_priority != Some(priority) {
+            self.ui.set_priority(priority);
+        }
+
+        let position = self.logs.as_ref().and_then(|logs_arc| {
+            let logs_map = logs_arc.lock().ok()?;
+            logs_map
+                .get(&priority)?
+                .iter()
+                .position(|log| log.cursor == cursor)
+        });
+
+        match position {
+            Some(position) => {
+                self.reveal_log_line(position);
+                self.ui.set_current_line(position);
+            }
+            None => self
+                .ui
+                .notify(format!("Mark {} is not in the loaded logs", letter), true),
+        }
+    }
+
+    // A count the logs got without a motion after it is the priority of
+    // 1-7, a started sequence is dropped.
+    pub fn expire_pending_keys(&mut self) {
+        if self.ui.pending_keys.is_expired() {
+            self.ui.pending_keys.clear();
+        }
+    }
+
+    // a digit in the logs switches the priority at once, what was shown is
+    // kept in case a motion follows and the digit was a count
+    pub fn switch_priority_from_digit(&mut self, digit: usize) -> Option<ShownPriority> {
+        if !self.ui.is_in_logs || !(1..=7).contains(&digit) {
+            return None;
+        }
+
+        let shown = ShownPriority {
+            priority: self.ui.selected_priority,
+            line: self.ui.current_line,
+            visual_anchor: self.ui.visual_anchor,
+        };
+        self.ui.set_priority(digit as u8);
+        Some(shown)
+    }
+
+    pub fn restore_priority(&mut self, shown: ShownPriority) {
+        self.ui.selected_priority = shown.priority;
+        self.ui.current_line = shown.line;
+        self.ui.visual_anchor = shown.visual_anchor;
+    }
+
+    // [ goes to the start of the invocation first, then to the previous one
+    pub fn jump_to_invocation(&mut self, forward: bool) {
+        let Some(groups) = self.get_invocation_groups().map(<[_]>::to_vec) else {
+            return;
+        };
+
+        let line = self.ui.current_line;
+        let Some(current) = groups.iter().position(|group| group.contains(line)) else {
+            return;
+        };
+
+        let target = if forward {
+            groups.get(current + 1)
+        } else if groups[current].first_line < line {
+            groups.get(current)
+        } else {
+            current.checked_sub(1).and_then(|idx| groups.get(idx))
+        };
+
+        if let Some(group) = target {
+            self.ui.set_current_line(group.first_line);
+        }
+    }
+
+    pub fn toggle_fold_invocation(&mut self) {
+        let Some(groups) = self.get_invocation_groups().map(<[_]>::to_vec) else {
+            return;
+        };
+
+        let line = self.ui.current_line;
+        if let Some(group) = groups.iter().find(|group| group.contains(line)) {
+            if !self.folded_invocations.remove(&group.id) {
+                self.folded_invocations.insert(group.id.clone());
+                self.ui.set_current_line(group.first_line);
+            }
+        }
+    }
+
+    // folds every invocation but the newest, or unfolds all of them
+    pub fn toggle_fold_old_invocations(&mut self) {
+        if !self.folded_invocations.is_empty() {
+            self.folded_invocations.clear();
+            return;
+        }
+
+        let Some(groups) = self.get_invocation_groups().map(<[_]>::to_vec) else {
+            return;
+        };
+
+        let Some(newest) = groups.first().map(|group| group.id.clone()) else {
+            return;
+        };
+        self.folded_invocations = groups
+            .iter()
+            .map(|group| group.id.clone())
+            .filter(|id| *id != newest)
+            .collect();
+
+        let line = self.ui.current_line;
+        if let Some(group) = groups
+            .iter()
+            .find(|group| group.contains(line) && group.id != newest)
+        {
+            self.ui.set_current_line(group.first_line);
+        }
+    }
+
+    pub fn get_duplicate_runs(&self) -> &[DuplicateRun] {
+        self.ui
+            .selected_priority
+            .and_then(|priority| self.duplicate_runs.get(&priority))
+            .map(|runs| runs.as_slice())
+            .unwrap_or_default()
+    }
+
+    // the runs that were not expanded with e
+    pub fn get_collapsed_runs(&self) -> Vec<DuplicateRun> {
+        self.get_duplicate_runs()
+            .iter()
+            .filter(|run| !self.expanded_duplicates.contains(&run.cursor))
+            .cloned()
+            .collect()
+    }
+
+    // u, off -> identical messages -> identical templates
+    pub fn cycle_dedupe_mode(&mut self) {
+        self.ui.dedupe = self.ui.dedupe.next();
+        self.expanded_duplicates.clear();
+        self.update_duplicate_runs();
+
+        let line = self.ui.current_line;
+        if let Some(run) = self
+            .get_collapsed_runs()
+            .into_iter()
+            .find(|run| run.contains(line))
+        {
+            self.ui.set_current_line(run.first_line);
+        }
+    }
+
+    pub fn toggle_expand_duplicates(&mut self) {
+        let line = self.ui.current_line;
+        let Some(run) = self
+            .get_duplicate_runs()
+            .iter()
+            .find(|run| run.contains(line))
+            .cloned()
+        else {
+            return;
+        };
+
+        if self.expanded_duplicates.remove(&run.cursor) {
+            self.ui.set_current_line(run.first_line);
+        } else {
+            self.expanded_duplicates.insert(run.cursor);
+        }
+    }
+
+    // The visual selection, else the entries behind a collapsed row or the
+    // line under the cursor.
+    fn get_yank_range(&self) -> (usize, usize) {
+        if let Some(selection) = self.ui.get_selection() {
+            return selection;
+        }
+
+        let line = self.ui.current_line;
+        match self
+            .get_collapsed_runs()
+            .into_iter()
+            .find(|run| run.first_line == line)
+        {
+            Some(run) => (run.first_line, run.first_line + run.count - 1),
+            None => (line, line),
+        }
+    }
+
+    fn get_log_range(&self, (first, last): (usize, usize)) -> Vec<JournalLog> {
+        self.logs
+            .as_ref()
+            .and_then(|logs_arc| {
+                let logs_map = logs_arc.lock().ok()?;
+                let entries = logs_map.get(&self.ui.selected_priority?)?;
+                entries
+                    .get(first..=last.min(entries.len().saturating_sub(1)))
+                    .map(|entries| entries.to_vec())
+            })
+            .unwrap_or_default()
+    }
+
+    // y, formatted by Options::yank, the visual selection ends with it
+    pub async fn yank_logs(&mut self) {
+        let entries = self.get_log_range(self.get_yank_range());
+        if entries.is_empty() {
+            return;
+        }
+
+        let content = format_yank_lines(&entries, &self.config.options.yank);
+        match yank_to_clipboard(&self.clipboard, &content).await {
+            Ok(()) => self.ui.notify(
+                format!(
+                    "Yanked {} {} to {}",
+                    entries.len(),
+                    if entries.len() == 1 {
+                        "entry"
+                    } else {
+                        "entries"
+                    },
+                    self.clipboard.name()
+                ),
+                false,
+            ),
+            Err(e) => {
+                error!("{}", e);
+                self.ui.notify(e.to_string(), true);
+            }
+        }
+        self.ui.visual_anchor = None;
+    }
+
+    // w, the prompt starts with a file name after the unit
+    pub fn open_export_prompt(&mut self) {
+        if self.logs.is_none() {
+            return;
+        }
+
+        self.ui.export_path =
+            default_export_path(self.selected_service.as_deref().unwrap_or("logs"));
+        self.ui.set_is_in_export_prompt(true);
+    }
 
-// TODO
-#[derive(PartialEq)]
-pub enum Events {
-    Quit,
-    GetLogs,
-    GetHelp,
-    GetLineInModal,
-    Search,
-    Docs,
-}
+    pub fn cycle_export_format(&mut self) {
+        self.ui.export_path = cycle_export_extension(&self.ui.export_path);
+    }
 
-// TODO:
-//      - align items and title columns vertically
-//      - filtering based on status (failed | running | exited)
-//      - actions for: start | stop | status
+    // the visual selection or the entries of the shown priority as listed
+    pub async fn export_logs(&mut self, overwrite: bool) {
+        let entries = match self.ui.get_selection() {
+            Some(selection) => self.get_log_range(selection),
+            None => self.get_shown_logs(),
+        };
 
-#[derive(Debug)]
-pub struct App {
-    pub ui: UI,
-    pub config: Config,
-    pub is_running: bool,
-    pub logs: Option<SharedJournalLogs>,
-    pub services: Option<(Vec<ServiceUnits>, Vec<ServiceUnitFiles>)>,
-    pub selected_service: Option<String>,
-}
+        let path = self.ui.export_path.trim().to_string();
+        let title = format!(
+            "{} -- {}",
+            self.selected_service.as_deref().unwrap_or("Logs"),
+            map_to_priority_str(&self.get_priority())
+        );
 
-impl App {
-    pub fn new(config: Config) -> Self {
-        Self {
-            ui: UI::new(),
-            config,
-            is_running: true,
-            logs: None,
-            services: None,
-            selected_service: None,
+        let exported = export_logs(
+            &path,
+            &entries,
+            &self.bookmarks,
+            &title,
+            &self.config,
+            overwrite,
+        )
+        .await;
+
+        // the selection stays until the overwrite is answered
+        match exported {
+            Ok(count) => {
+                info!("Exported {} entries to {}", count, path);
+                self.ui
+                    .notify(format!("Exported {} entries to {}", count, path), false);
+            }
+            Err(RounalError::ExportFileExists(_)) => {
+                self.ui.confirm_question = format!("Overwrite {}?", path);
+                self.ui.set_is_in_confirm_prompt(true);
+                self.confirm_event = Some(Events::OverwriteExport);
+                return;
+            }
+            Err(e) => {
+                error!("{}", e);
+                self.ui.notify(e.to_string(), true);
+            }
         }
+        self.ui.visual_anchor = None;
     }
 
-    pub fn set_is_running(&mut self, state: bool) {
-        self.is_running = state;
+    // like the bookmarks, a file that cannot be read is not written over
+    pub async fn load_marks(&mut self) {
+        match load_marks(&self.config.options.marks_file).await {
+            Ok(marks) => {
+                self.marks = marks;
+                self.are_marks_saved = true;
+            }
+            Err(e) => {
+                error!("{}", e);
+                self.are_marks_saved = false;
+                self.ui.notify(format!("{}, marks are not saved", e), true);
+            }
+        }
     }
 
-    pub fn set_services(
-        &mut self,
-        services: (Vec<ServiceUnits>, Vec<ServiceUnitFiles>),
-    ) -> Result<()> {
-        self.services = Some(services);
-        Ok(())
+    pub async fn save_marks(&mut self) {
+        if !self.are_marks_saved {
+            self.ui.notify(
+                format!(
+                    "Marks are not saved, {} could not be read",
+                    self.config.options.marks_file
+                ),
+                true,
+            );
+            return;
+        }
+        if let Err(e) = save_marks(&self.config.options.marks_file, &self.marks).await {
+            error!("{}", e);
+            self.ui.notify(e.to_string(), true);
+        }
     }
 
-    pub fn set_logs(&mut self, logs: Arc<Mutex<JournalLogMap>>) {
-        self.logs = Some(logs);
+    // a file that cannot be read is not written over, the bookmarks in it
+    // would be lost
+    pub async fn load_bookmarks(&mut self) {
+        match load_bookmarks(&self.config.options.bookmarks_file).await {
+            Ok(bookmarks) => {
+                self.bookmarks = bookmarks;
+                self.are_bookmarks_saved = true;
+            }
+            Err(e) => {
+                error!("{}", e);
+                self.are_bookmarks_saved = false;
+                self.ui
+                    .notify(format!("{}, bookmarks are not saved", e), true);
+            }
+        }
     }
 
-    pub fn clear_logs(&mut self) {
-        self.logs = None;
+    pub async fn save_bookmarks(&mut self) {
+        if !self.are_bookmarks_saved {
+            self.ui.notify(
+                format!(
+                    "Bookmarks are not saved, {} could not be read",
+                    self.config.options.bookmarks_file
+                ),
+                true,
+            );
+            return;
+        }
+        if let Err(e) = save_bookmarks(&self.config.options.bookmarks_file, &self.bookmarks).await {
+            error!("{}", e);
+            self.ui.notify(e.to_string(), true);
+        }
+    }
+
+    // Bookmarks of a merged view are opened with the unit of the entry,
+    // the kernel log and the journal with themselves.
+    fn get_current_bookmark(&self) -> Option<Bookmark> {
+        let Some(CurrentLine::Log(log)) = self.ui.get_current_line(self) else {
+            return None;
+        };
+        if let Some(bookmark) = find_bookmark(&self.bookmarks, &log.cursor) {
+            return Some(bookmark.clone());
+        }
+
+        let unit = match self.selected_units.as_slice() {
+            [unit] => unit.clone(),
+            _ => log.unit.clone(),
+        };
+        Some(Bookmark::new(&log, &unit))
+    }
+
+    // b, pressed again on a bookmarked entry it removes the bookmark and its note
+    pub fn toggle_bookmark(&mut self) -> Option<Events> {
+        let bookmark = self.get_current_bookmark()?;
+
+        if find_bookmark(&self.bookmarks, &bookmark.cursor).is_some() {
+            self.bookmarks
+                .retain(|other| other.cursor != bookmark.cursor);
+            self.ui.notify("Bookmark removed".to_string(), false);
+        } else {
+            insert_bookmark(&mut self.bookmarks, bookmark);
+            self.ui.notify("Bookmark added".to_string(), false);
+        }
+        Some(Events::SaveBookmarks)
+    }
+
+    // a, the entry is bookmarked along with its note
+    pub fn open_annotation_prompt(&mut self) {
+        let bookmark = if self.ui.is_showing_bookmarks {
+            self.bookmarks.get(self.ui.bookmarks_line).cloned()
+        } else {
+            self.get_current_bookmark()
+        };
+
+        if let Some(bookmark) = bookmark {
+            self.ui.annotation = bookmark.note.clone();
+            self.annotating = Some(bookmark);
+            self.ui.set_is_in_annotation_prompt(true);
+        }
+    }
+
+    pub fn save_annotation(&mut self) -> Option<Events> {
+        let mut bookmark = self.annotating.take()?;
+        bookmark.note = self.ui.annotation.trim().to_string();
+        insert_bookmark(&mut self.bookmarks, bookmark);
+        Some(Events::SaveBookmarks)
+    }
+
+    pub fn move_bookmarks_cursor(&mut self, forward: bool) {
+        let line = self.ui.bookmarks_line;
+        self.ui.bookmarks_line = if forward {
+            (line + 1).min(self.bookmarks.len().saturating_sub(1))
+        } else {
+            line.saturating_sub(1)
+        };
+    }
+
+    pub fn delete_bookmark(&mut self) -> Option<Events> {
+        if self.ui.bookmarks_line >= self.bookmarks.len() {
+            return None;
+        }
+
+        self.bookmarks.remove(self.ui.bookmarks_line);
+        self.ui.bookmarks_line = self
+            .ui
+            .bookmarks_line
+            .min(self.bookmarks.len().saturating_sub(1));
+        Some(Events::SaveBookmarks)
+    }
+
+    // the unit is opened at the priority of the entry, like a search result
+    pub fn open_bookmark(&mut self) -> Option<Events> {
+        let bookmark = self.bookmarks.get(self.ui.bookmarks_line)?.clone();
+
+        info!("OPENING BOOKMARK {} {}", bookmark.unit, bookmark.cursor);
+        self.ui.set_is_showing_bookmarks(false);
+        let event = self.open_service(bookmark.unit);
+        self.jump_to_cursor = Some(bookmark.cursor);
+        self.ui.set_priority(bookmark.priority);
+        event
+    }
+
+    // the unit of the open logs or the highlighted one, not a merged view
+    fn get_target_unit(&self) -> Option<String> {
+        let unit = if self.ui.is_in_logs {
+            match self.selected_units.as_slice() {
+                [unit] => Some(unit.clone()),
+                _ => None,
+            }
+        } else {
+            self.get_highlighted_service()
+        };
+        unit.filter(|unit| !is_journal_source(unit))
+    }
+
+    // R
+    pub fn open_bundle_prompt(&mut self) {
+        match self.get_target_unit() {
+            Some(unit) => {
+                self.bundle_unit = Some(unit);
+                self.ui.bundle_since = self.config.options.search_since.clone();
+                self.ui.set_is_in_bundle_prompt(true);
+            }
+            _ => self
+                .ui
+                .notify("A bundle is made for a single unit".to_string(), true),
+        }
+    }
+
+    // the artefacts are collected in the background, the result is
+    // reported in the bottom bar once they are written
+    pub fn create_bundle(&mut self) {
+        let Some(unit) = self.bundle_unit.take() else {
+            return;
+        };
+        if self.bundle_loading.is_some() {
+            self.ui
+                .notify("A bundle is already being written".to_string(), true);
+            return;
+        }
+
+        self.ui
+            .notify(format!("Collecting a bundle of {}...", unit), false);
+        let since = self.ui.bundle_since.trim().to_string();
+        self.bundle_loading = Some(tokio::spawn(
+            async move { create_bundle(&unit, &since).await },
+        ));
+    }
+
+    pub async fn poll_bundle(&mut self) {
+        if !self
+            .bundle_loading
+            .as_ref()
+            .is_some_and(|handle| handle.is_finished())
+        {
+            return;
+        }
+
+        let Some(handle) = self.bundle_loading.take() else {
+            return;
+        };
+
+        let created = match handle.await {
+            Ok(created) => created,
+            Err(e) => {
+                error!("Bundle task failed: {}", e);
+                return;
+            }
+        };
+
+        match created {
+            Ok(report) if report.failed > 0 => self.ui.notify(
+                format!(
+                    "Wrote {}, {} of {} artefacts failed, see MANIFEST.json",
+                    report.path,
+                    report.failed,
+                    report.collected + report.failed
+                ),
+                true,
+            ),
+            Ok(report) => self.ui.notify(format!("Wrote {}", report.path), false),
+            Err(e) => {
+                error!("{}", e);
+                self.ui.notify(e.to_string(), true);
+            }
+        }
+    }
+
+    fn get_unit_names(&self) -> Vec<String> {
+        let units = self.services.iter().flat_map(|(units, _)| units);
+        self.journal_sources
+            .iter()
+            .chain(units)
+            .map(|unit| unit.name.clone())
+            .collect()
+    }
+
+    // Tab and BackTab in the command line
+    pub fn complete_command(&mut self, forward: bool) {
+        let (typed, selected) = match self.ui.command_completion.take() {
+            Some(completion) => (completion.typed, Some(completion.selected)),
+            None => (self.ui.command_line.clone(), None),
+        };
+
+        let candidates = get_completions(&typed, &self.get_unit_names());
+        let len = candidates.len();
+        if len == 0 {
+            return;
+        }
+
+        let selected = match (selected, forward) {
+            (Some(idx), true) => (idx + 1) % len,
+            (Some(idx), false) => (idx + len - 1) % len,
+            (None, true) => 0,
+            (None, false) => len - 1,
+        };
+
+        self.ui.command_line = complete_line(&typed, &candidates[selected]);
+        self.ui.command_completion = Some(CommandCompletion {
+            typed,
+            candidates,
+            selected,
+        });
+    }
+
+    // actions are run by the input handler, errors end up in the bottom bar
+    pub fn run_command(&mut self, command: Command) -> Option<Events> {
+        match command {
+            Command::Unit(name) => {
+                let units = self.get_unit_names();
+                let service_name = format!("{}.service", name);
+                match units
+                    .into_iter()
+                    .find(|unit| *unit == name || *unit == service_name)
+                {
+                    Some(unit) => self.open_service(unit),
+                    None => {
+                        self.ui.notify(format!("No unit named {}", name), true);
+                        None
+                    }
+                }
+            }
+            Command::Priority(priority) => {
+                self.ui.set_priority(priority);
+                None
+            }
+            Command::Since(since) => {
+                self.log_window.since = since;
+                self.reload_logs()
+            }
+            Command::Boot(boot) => {
+                self.log_window.boot = boot;
+                self.reload_logs()
+            }
+            Command::Jump(None) => {
+                self.ui.set_is_in_jump_prompt(true);
+                None
+            }
+            Command::Jump(Some(input)) => self.jump_to_time(&input),
+            Command::Export(None) => {
+                self.open_export_prompt();
+                None
+            }
+            Command::Export(Some(path)) => {
+                if self.logs.is_none() {
+                    self.ui.notify("No logs to export".to_string(), true);
+                    return None;
+                }
+                self.ui.export_path = path;
+                Some(Events::Export)
+            }
+            Command::UnitAction(action) => match self.get_target_unit() {
+                Some(unit) => {
+                    self.ui.confirm_question =
+                        format!("{} {}?", action.name().to_uppercase(), unit);
+                    self.ui.set_is_in_confirm_prompt(true);
+                    self.pending_unit_action = Some((action, unit));
+                    self.confirm_event = Some(Events::UnitAction);
+                    None
+                }
+                None => {
+                    self.ui.notify(
+                        format!("Nothing to {}, select a single unit", action.name()),
+                        true,
+                    );
+                    None
+                }
+            },
+            Command::Set(option, value) => {
+                match option.as_str() {
+                    "wrap" => self.ui.is_wrapping = value,
+                    "invocations" => {
+                        self.ui.is_grouping_invocations = value;
+                        self.folded_invocations.clear();
+                    }
+                    _ => {}
+                }
+                None
+            }
+            Command::Theme(theme) => {
+                self.config.set_theme(&theme);
+                Some(Events::Theme)
+            }
+            Command::Action(_) => None,
+        }
+    }
+
+    // the open logs are fetched again with the new time window
+    fn reload_logs(&mut self) -> Option<Events> {
+        if self.selected_units.is_empty() {
+            return None;
+        }
+        self.ui.set_current_line(0);
+        Some(Events::GetLogs)
+    }
+
+    pub async fn run_unit_action(&mut self) {
+        let Some((action, unit)) = self.pending_unit_action.take() else {
+            return;
+        };
+
+        match run_unit_action(action, &unit).await {
+            Ok(()) => {
+                self.ui
+                    .notify(format!("{} {}", action.past_tense(), unit), false);
+                match get_system_services().await {
+                    Ok(services) => self.services = Some(services),
+                    Err(e) => error!("Refreshing units failed: {}", e),
+                }
+            }
+            Err(e) => {
+                error!("{}", e);
+                self.ui.notify(e.to_string(), true);
+            }
+        }
+    }
+
+    // Tab in the split layout, an already opened service keeps its cursor
+    pub fn focus_logs_pane(&mut self) -> Option<Events> {
+        if self.logs.is_some() && self.get_highlighted_service() == self.selected_service {
+            self.ui.focus_logs();
+            return None;
+        }
+        self.open_highlighted_service()
+    }
+
+    // the preview is fetched once the cursor has rested on a unit for a moment
+    pub fn update_preview(&mut self) {
+        if !self.is_split_layout() || self.ui.is_in_logs {
+            return;
+        }
+
+        let Some(service) = self.get_highlighted_service() else {
+            return;
+        };
+
+        let Ok(mut requested) = self.preview_requested.lock() else {
+            return;
+        };
+        if requested.as_ref() == Some(&service) {
+            return;
+        }
+
+        match &self.preview_candidate {
+            Some((candidate, at)) if *candidate == service => {
+                if Instant::now() < *at {
+                    return;
+                }
+            }
+            _ => {
+                self.preview_candidate = Some((service, Instant::now() + PREVIEW_DELAY));
+                return;
+            }
+        }
+
+        info!("requesting preview for {}", service);
+        *requested = Some(service.clone());
+        drop(requested);
+        // a failed fetch is tried again after a while
+        self.preview_candidate = Some((service.clone(), Instant::now() + PREVIEW_RETRY));
+        let requested = self.preview_requested.clone();
+        let preview = self.preview.clone();
+        let priority = self
+            .ui
+            .selected_priority
+            .unwrap_or(self.config.options.initial_priority);
+
+        // a slow fetch of a unit the cursor has left is dropped, it would
+        // replace the preview of the unit that is highlighted now
+        tokio::spawn(async move {
+            let logs = get_recent_logs(&service, priority, PREVIEW_LINES).await;
+
+            let Ok(mut requested) = requested.lock() else {
+                return;
+            };
+            if requested.as_ref() != Some(&service) {
+                info!("dropping preview of {}", service);
+                return;
+            }
+            match logs {
+                Ok(logs) => {
+                    if let Ok(mut preview) = preview.lock() {
+                        *preview = Some(Preview { service, logs });
+                    }
+                }
+                Err(e) => {
+                    error!("Preview failed for {}: {}", service, e);
+                    *requested = None;
+                }
+            }
+        });
+    }
+
+    pub fn get_sort(&self) -> &UnitSort {
+        match self.ui.view {
+            View::ServiceUnits | View::Dashboard => &self.unit_sort,
+            View::ServiceUnitFiles => &self.file_sort,
+        }
+    }
+
+    pub fn cycle_sort_column(&mut self) {
+        match self.ui.view {
+            View::ServiceUnits => self.unit_sort.cycle_column(SortList::Units),
+            View::ServiceUnitFiles => self.file_sort.cycle_column(SortList::Files),
+            View::Dashboard => return,
+        }
+        self.apply_sort();
+    }
+
+    pub fn toggle_sort_direction(&mut self) {
+        match self.ui.view {
+            View::ServiceUnits => self.unit_sort.toggle_direction(),
+            View::ServiceUnitFiles => self.file_sort.toggle_direction(),
+            View::Dashboard => return,
+        }
+        self.apply_sort();
+    }
+
+    pub fn apply_sort(&mut self) {
+        if let Some((units, files)) = &mut self.services {
+            self.unit_sort.sort_units(units);
+            self.file_sort.sort_unit_files(files);
+        }
+        self.ui.set_current_line(0);
+    }
+
+    pub fn open_global_search(&mut self) {
+        if self.ui.global_search_since.is_empty() {
+            self.ui.global_search_since = self.config.options.search_since.clone();
+        }
+        self.ui.is_editing_search_since = false;
+        self.ui.set_is_in_global_search_mode(true);
+    }
+
+    // group rows open the unit at that priority, entry rows also move
+    // the cursor onto the matching entry once the logs are loaded
+    pub fn open_global_search_result(&mut self) -> Option<Events> {
+        let row = self.global_search.as_ref()?.get_current_row()?.clone();
+
+        let (unit, priority, cursor) = match row {
+            SearchRow::Group { unit, priority, .. } => (unit, priority, None),
+            SearchRow::Entry(log) => (log.unit, log.priority.clamp(1, 7), Some(log.cursor)),
+        };
+
+        info!("OPENING SEARCH RESULT {} {}", unit, priority);
+        self.ui.set_is_showing_global_search(false);
+        let event = self.open_service(unit);
+        self.jump_to_cursor = cursor;
+        self.ui.set_priority(priority);
+        event
+    }
+
+    pub fn open_fuzzy_finder(&mut self) {
+        if let Some((units, files)) = &self.services {
+            let units: Vec<ServiceUnits> =
+                self.journal_sources.iter().chain(units).cloned().collect();
+            self.fuzzy_finder = Some(FuzzyFinder::new(&units, files));
+            self.ui.set_is_showing_fuzzy_finder(true);
+        }
+    }
+
+    pub fn close_fuzzy_finder(&mut self) {
+        self.fuzzy_finder = None;
+        self.ui.set_is_showing_fuzzy_finder(false);
+    }
+
+    pub fn open_fuzzy_finder_result(&mut self) -> Option<Events> {
+        let name = self.fuzzy_finder.as_ref()?.get_selected()?.name.clone();
+
+        info!("SELECTED SERVICE FROM FINDER {}", name);
+        self.close_fuzzy_finder();
+        self.open_service(name)
+    }
+
+    pub fn jump_to_pending_cursor(&mut self) {
+        let Some(cursor) = self.jump_to_cursor.take() else {
+            return;
+        };
+
+        let position = self.logs.as_ref().and_then(|logs_arc| {
+            let logs_map = logs_arc.lock().ok()?;
+            logs_map
+                .get(&self.ui.selected_priority?)?
+                .iter()
+                .position(|log| log.cursor == cursor)
+        });
+
+        if let Some(position) = position {
+            self.ui.set_current_line(position);
+        }
+    }
+
+    // t, a time outside the loaded logs fetches the logs around it once
+    pub fn jump_to_time(&mut self, input: &str) -> Option<Events> {
+        if !self.ui.is_in_logs || input.trim().is_empty() {
+            return None;
+        }
+
+        let Some(time) = parse_jump_time(input, Local::now()) else {
+            self.ui
+                .notify(format!("Unknown time: {}", input.trim()), true);
+            return None;
+        };
+        let realtime = time.timestamp_micros().max(0) as u64;
+
+        // entries are newest first
+        let loaded = self.logs.as_ref().and_then(|logs_arc| {
+            let logs_map = logs_arc.lock().ok()?;
+            let entries = logs_map.get(&self.ui.selected_priority?)?;
+            Some((entries.last()?.realtime, entries.first()?.realtime))
+        });
+
+        self.jump_to_realtime = Some(realtime);
+        if loaded.is_some_and(|(oldest, newest)| (oldest..=newest).contains(&realtime)) {
+            self.jump_to_pending_realtime();
+            return None;
+        }
+
+        // the whole journal is cut to its newest entries, so it ends close to
+        // the time for the cut to keep the entries before it
+        let after = if self
+            .selected_units
+            .iter()
+            .any(|unit| unit == ENTIRE_JOURNAL)
+        {
+            JUMP_AFTER_LIMITED
+        } else {
+            JUMP_MARGIN
+        };
+        let format = |time: Option<DateTime<Local>>| {
+            time.map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+        };
+        info!("jumping outside the loaded logs, fetching around {}", time);
+        self.jump_window = Some(TimeWindow {
+            since: format(time.checked_sub_signed(JUMP_MARGIN)),
+            until: format(time.checked_add_signed(after)),
+            boot: self.log_window.boot.clone(),
+        });
+        self.reload_logs()
+    }
+
+    // a jump does not carry over to another tab or a failed fetch
+    fn cancel_pending_jump(&mut self) {
+        self.jump_to_realtime = None;
+        self.jump_window = None;
+    }
+
+    // the entry closest to the time, after the logs were fetched for it
+    pub fn jump_to_pending_realtime(&mut self) {
+        let Some(realtime) = self.jump_to_realtime.take() else {
+            return;
+        };
+
+        let line = self.logs.as_ref().and_then(|logs_arc| {
+            let logs_map = logs_arc.lock().ok()?;
+            find_nearest_entry(logs_map.get(&self.ui.selected_priority?)?, realtime)
+        });
+
+        match line {
+            Some(line) => {
+                self.reveal_log_line(line);
+                self.ui.set_current_line(line);
+            }
+            None => self
+                .ui
+                .notify("No log entries around that time".to_string(), true),
+        }
     }
 
     pub fn reorder_lines(&mut self) {
@@ -113,6 +1897,8 @@ impl App {
                     }
                 }
             }
+            self.update_invocation_groups();
+            self.update_duplicate_runs();
         } else if let Some((units, files)) = &mut self.services {
             match self.ui.view {
                 View::ServiceUnits => {
@@ -126,6 +1912,7 @@ impl App {
                         }
                     });
                 }
+                View::Dashboard => {}
                 View::ServiceUnitFiles => {
                     files.sort_by_key(|f| {
                         if f.name.to_lowercase().contains(&q) {
@@ -141,30 +1928,68 @@ impl App {
     }
 }
 
-pub async fn start_application(config: Config) -> Result<()> {
-    let mut stdout = stdout();
-    enable_raw_mode()?;
-    stdout.execute(EnterAlternateScreen)?;
+// Puts the terminal back when the app returns, fails or panics, errors are
+// ignored as there is nothing left to report them to.
+struct TerminalGuard {
+    mouse: bool,
+}
 
-    {
-        let backend = CrosstermBackend::new(&mut stdout);
-        let mut terminal = Terminal::new(backend).map_err(RounalError::TerminalError)?;
+impl TerminalGuard {
+    fn new(mouse: bool) -> Result<Self> {
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            restore_terminal(mouse);
+            previous_hook(info);
+        }));
+
+        let guard = Self { mouse };
+        enable_raw_mode()?;
+        stdout().execute(EnterAlternateScreen)?;
+        // capturing the mouse takes text selection away from the terminal
+        if mouse {
+            stdout().execute(EnableMouseCapture)?;
+        }
+        Ok(guard)
+    }
+}
 
-        let styler = Styler::new(&config);
-        let mut app = App::new(config);
-        let services = get_system_services().await?;
-        app.set_services(services)?;
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal(self.mouse);
+    }
+}
 
-        run(&mut terminal, app, styler).await?;
+fn restore_terminal(mouse: bool) {
+    let mut stdout = stdout();
+    if mouse {
+        let _ = stdout.execute(DisableMouseCapture);
     }
+    let _ = disable_raw_mode();
+    let _ = stdout.execute(LeaveAlternateScreen);
+}
 
-    disable_raw_mode()?;
-    stdout.execute(LeaveAlternateScreen)?;
+pub async fn start_application(config: Config) -> Result<()> {
+    let _guard = TerminalGuard::new(config.options.mouse)?;
 
-    Ok(())
+    let mut stdout = stdout();
+    let backend = CrosstermBackend::new(&mut stdout);
+    let mut terminal = Terminal::new(backend).map_err(RounalError::TerminalError)?;
+
+    let styler = Styler::new(&config);
+    let mut app = App::new(config);
+    app.load_bookmarks().await;
+    app.load_marks().await;
+    let services = get_system_services().await?;
+    app.set_services(services)?;
+
+    run(&mut terminal, app, styler).await
 }
 
-async fn run<B: Backend>(terminal: &mut Terminal<B>, mut app: App, styler: Styler) -> Result<()> {
+async fn run<B: Backend>(
+    terminal: &mut Terminal<B>,
+    mut app: App,
+    mut styler: Styler,
+) -> Result<()> {
     while app.is_running {
         terminal.draw(|frame| {
             draw_ui(frame, &app, &styler).ok();
@@ -180,24 +2005,102 @@ async fn run<B: Backend>(terminal: &mut Terminal<B>, mut app: App, styler: Style
             if app.ui.is_showing_docs {
                 draw_docs_modal(frame, &styler).ok();
             }
+
+            if app.ui.is_showing_global_search {
+                draw_global_search(frame, &app, &styler).ok();
+            }
+
+            if app.ui.is_showing_fuzzy_finder {
+                draw_fuzzy_finder(frame, &app, &styler).ok();
+            }
+
+            if app.ui.is_showing_filters {
+                draw_filters_modal(frame, &app, &styler).ok();
+            }
+
+            if app.ui.is_showing_match_builder {
+                draw_match_builder(frame, &app, &styler).ok();
+            }
+
+            if app.ui.is_showing_patterns {
+                draw_patterns(frame, &app, &styler).ok();
+            }
+
+            if app.ui.is_showing_bookmarks {
+                draw_bookmarks(frame, &app, &styler).ok();
+            }
         })?;
 
-        if let Some(event) = handle_key_events(&mut app) {
+        let event = handle_key_events(&mut app);
+        app.expire_pending_keys();
+        app.update_preview();
+        app.poll_tabs().await;
+        app.poll_match_values().await;
+        app.poll_dashboard().await;
+        app.poll_bundle().await;
+
+        if let Some(event) = event {
             match event {
                 Events::Quit => app.set_is_running(false),
                 Events::Search => app.ui.set_is_in_search_mode(true),
+                Events::Command => app.ui.set_is_in_command_mode(true),
                 Events::GetHelp => app.ui.set_is_showing_help(!app.ui.is_showing_help),
                 Events::Docs => app.ui.set_is_showing_docs(!app.ui.is_showing_docs),
                 Events::GetLineInModal => app
                     .ui
                     .set_is_showing_line_in_modal(!app.ui.is_showing_line_in_modal),
                 Events::GetLogs => {
-                    if let Some(service) = &app.selected_service {
+                    if !app.selected_units.is_empty() {
                         info!("start getting journals");
-                        let all_logs_for_service = get_journal_logs(service).await?;
-                        app.set_logs(all_logs_for_service);
-                        info!("journals set to app");
+                        let window = app
+                            .jump_window
+                            .take()
+                            .unwrap_or_else(|| app.log_window.clone());
+                        match get_journal_logs(&app.selected_units, &app.journal_matches, &window)
+                            .await
+                        {
+                            Ok(all_logs_for_service) => {
+                                app.set_logs(all_logs_for_service);
+                                app.jump_to_pending_cursor();
+                                app.jump_to_pending_realtime();
+                                info!("journals set to app");
+                            }
+                            Err(e) => {
+                                error!("{}", e);
+                                app.cancel_pending_jump();
+                                app.ui.notify(e.to_string(), true);
+                            }
+                        }
+                    }
+                }
+                Events::GlobalSearch => app.open_global_search(),
+                Events::FuzzyFinder => app.open_fuzzy_finder(),
+                Events::Filters => app.ui.set_is_showing_filters(!app.ui.is_showing_filters),
+                Events::GetFieldValues => app.load_match_values(),
+                Events::Dashboard => app.load_dashboard(),
+                Events::Export => app.export_logs(false).await,
+                Events::OverwriteExport => app.export_logs(true).await,
+                Events::Yank => app.yank_logs().await,
+                Events::Bundle => app.create_bundle(),
+                Events::UnitAction => app.run_unit_action().await,
+                Events::Theme => styler = Styler::new(&app.config),
+                Events::SaveBookmarks => app.save_bookmarks().await,
+                Events::SaveMarks => app.save_marks().await,
+                Events::RunGlobalSearch => {
+                    let mut search =
+                        GlobalSearch::new(&app.ui.global_search_query, &app.ui.global_search_since);
+
+                    match search_journal(&search.query, &search.since).await {
+                        Ok(logs) => search.set_results(logs),
+                        Err(e) => {
+                            error!("Global search failed: {}", e);
+                            search.set_error(e.to_string());
+                        }
                     }
+
+                    app.global_search = Some(search);
+                    app.ui.set_is_in_global_search_mode(false);
+                    app.ui.set_is_showing_global_search(true);
                 }
             }
         }
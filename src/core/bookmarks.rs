@@ -0,0 +1,123 @@
+use crate::{
+    core::{
+        error::{Result, RounalError},
+        journal::JournalLog,
+    },
+    util::{expand_home, write_file_atomic},
+};
+use serde::{Deserialize, Serialize};
+
+pub const BOOKMARK_SYMBOL: &str = "●";
+pub const NOTE_SYMBOL: &str = "✎";
+
+// An entry is found again by its journal cursor, the rest is kept so the
+// list of bookmarks does not need the logs of every unit loaded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub cursor: String,
+    pub unit: String,
+    pub priority: u8,
+    pub realtime: u64,
+    pub timestamp: String,
+    pub message: String,
+    #[serde(default)]
+    pub note: String,
+}
+
+impl Bookmark {
+    // the unit is the one the entry is opened with again from the list
+    pub fn new(log: &JournalLog, unit: &str) -> Self {
+        Self {
+            cursor: log.cursor.clone(),
+            unit: unit.to_string(),
+            priority: log.priority.clamp(1, 7),
+            realtime: log.realtime,
+            timestamp: log.timestamp.clone(),
+            message: log.log_message.clone(),
+            note: "".to_string(),
+        }
+    }
+
+    pub fn get_marker(&self) -> &'static str {
+        if self.note.is_empty() {
+            BOOKMARK_SYMBOL
+        } else {
+            NOTE_SYMBOL
+        }
+    }
+}
+
+pub fn find_bookmark<'a>(bookmarks: &'a [Bookmark], cursor: &str) -> Option<&'a Bookmark> {
+    bookmarks.iter().find(|bookmark| bookmark.cursor == cursor)
+}
+
+// newest entries first, like the logs
+pub fn insert_bookmark(bookmarks: &mut Vec<Bookmark>, bookmark: Bookmark) {
+    bookmarks.retain(|other| other.cursor != bookmark.cursor);
+    let idx = bookmarks.partition_point(|other| other.realtime > bookmark.realtime);
+    bookmarks.insert(idx, bookmark);
+}
+
+// a missing file is no bookmarks yet
+pub async fn load_bookmarks(path: &str) -> Result<Vec<Bookmark>> {
+    let path = expand_home(path);
+    match tokio::fs::read_to_string(&path).await {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| RounalError::BookmarkError(format!("{}: {}", path, e))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(RounalError::BookmarkError(format!("{}: {}", path, e))),
+    }
+}
+
+pub async fn save_bookmarks(path: &str, bookmarks: &[Bookmark]) -> Result<()> {
+    let path = expand_home(path);
+    let content = serde_json::to_string_pretty(bookmarks)
+        .map_err(|e| RounalError::BookmarkError(e.to_string()))?;
+
+    write_file_atomic(&path, &content)
+        .await
+        .map_err(|e| RounalError::BookmarkError(format!("{}: {}", path, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bookmark(cursor: &str, realtime: u64) -> Bookmark {
+        let log = JournalLog {
+            cursor: cursor.to_string(),
+            realtime,
+            ..Default::default()
+        };
+        Bookmark::new(&log, "nginx.service")
+    }
+
+    fn cursors(bookmarks: &[Bookmark]) -> Vec<&str> {
+        bookmarks
+            .iter()
+            .map(|bookmark| bookmark.cursor.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn inserts_newest_first() {
+        let mut bookmarks = vec![];
+        for (cursor, realtime) in [("b", 20), ("a", 10), ("d", 40), ("c", 30)] {
+            insert_bookmark(&mut bookmarks, bookmark(cursor, realtime));
+        }
+
+        assert_eq!(cursors(&bookmarks), vec!["d", "c", "b", "a"]);
+    }
+
+    #[test]
+    fn replaces_a_bookmark_of_the_same_entry() {
+        let mut bookmarks = vec![bookmark("c", 30), bookmark("b", 20), bookmark("a", 10)];
+        let mut annotated = bookmark("b", 20);
+        annotated.note = "look here".to_string();
+        insert_bookmark(&mut bookmarks, annotated);
+
+        assert_eq!(cursors(&bookmarks), vec!["c", "b", "a"]);
+        assert_eq!(bookmarks[1].note, "look here");
+        assert_eq!(bookmarks[1].get_marker(), NOTE_SYMBOL);
+    }
+}
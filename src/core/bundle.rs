@@ -0,0 +1,249 @@
+use crate::{
+    core::error::{Result, RounalError},
+    util::sanitize_file_name,
+};
+use log::{error, info};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+const MANIFEST: &str = "MANIFEST.json";
+
+// one file of the bundle and the command whose output it holds
+struct Artefact {
+    file: &'static str,
+    description: String,
+    command: Vec<String>,
+}
+
+impl Artefact {
+    fn new(file: &'static str, description: &str, command: &[&str]) -> Self {
+        Self {
+            file,
+            description: description.to_string(),
+            command: command.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BundleReport {
+    pub path: String,
+    pub collected: usize,
+    pub failed: usize,
+}
+
+fn get_artefacts(unit: &str, since: &str) -> Vec<Artefact> {
+    vec![
+        Artefact::new(
+            "systemctl-show.txt",
+            "unit properties",
+            &["systemctl", "show", unit],
+        ),
+        Artefact::new(
+            "systemctl-cat.txt",
+            "unit file and drop-ins",
+            &["systemctl", "cat", unit],
+        ),
+        Artefact::new(
+            "systemctl-status.txt",
+            "current state and recent logs",
+            &["systemctl", "status", "--no-pager", "--full", unit],
+        ),
+        Artefact {
+            file: "logs.json",
+            description: format!("logs since {} as journal JSON", since),
+            command: [
+                "sudo",
+                "journalctl",
+                "-u",
+                unit,
+                "--since",
+                since,
+                "-o",
+                "json",
+            ]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect(),
+        },
+        Artefact::new(
+            "dependencies.txt",
+            "units this unit depends on",
+            &["systemctl", "list-dependencies", "--no-pager", unit],
+        ),
+        Artefact::new(
+            "reverse-dependencies.txt",
+            "units depending on this unit",
+            &[
+                "systemctl",
+                "list-dependencies",
+                "--reverse",
+                "--no-pager",
+                unit,
+            ],
+        ),
+        Artefact::new(
+            "host/uname.txt",
+            "kernel and architecture",
+            &["uname", "-a"],
+        ),
+        Artefact::new(
+            "host/os-release.txt",
+            "distribution",
+            &["cat", "/etc/os-release"],
+        ),
+        Artefact::new("host/hostnamectl.txt", "host names", &["hostnamectl"]),
+        Artefact::new("host/uptime.txt", "uptime and load", &["uptime"]),
+        Artefact::new(
+            "host/systemd-version.txt",
+            "systemd version and features",
+            &["systemctl", "--version"],
+        ),
+        Artefact::new(
+            "host/failed-units.txt",
+            "all failed units",
+            &["systemctl", "--failed", "--no-pager"],
+        ),
+    ]
+}
+
+// A failing command still leaves its file, with stderr when there is no
+// output, and the exit code and error in the manifest.
+async fn collect(dir: PathBuf, artefact: Artefact) -> serde_json::Value {
+    let output = Command::new(&artefact.command[0])
+        .args(&artefact.command[1..])
+        .output()
+        .await;
+
+    let (content, exit_code, error) = match output {
+        Ok(out) => {
+            let stderr = String::from_utf8_lossy(&out.stderr).trim().to_string();
+            let content = if out.stdout.is_empty() {
+                stderr.clone().into_bytes()
+            } else {
+                out.stdout
+            };
+            let error = (!out.status.success() && !stderr.is_empty()).then_some(stderr);
+            (content, out.status.code(), error)
+        }
+        Err(e) => (vec![], None, Some(e.to_string())),
+    };
+
+    let path = dir.join(artefact.file);
+    let written = match path.parent() {
+        Some(parent) => match tokio::fs::create_dir_all(parent).await {
+            Ok(()) => tokio::fs::write(&path, &content).await,
+            Err(e) => Err(e),
+        },
+        None => tokio::fs::write(&path, &content).await,
+    };
+    let error = match written {
+        Ok(()) => error,
+        Err(e) => Some(format!("writing {}: {}", artefact.file, e)),
+    };
+
+    json!({
+        "file": artefact.file,
+        "description": artefact.description,
+        "command": artefact.command.join(" "),
+        "exit_code": exit_code,
+        "bytes": content.len(),
+        "error": error,
+    })
+}
+
+async fn run_tar(archive: &Path, staging: &Path, name: &str) -> Result<()> {
+    let out = Command::new("tar")
+        .arg("-czf")
+        .arg(archive)
+        .arg("-C")
+        .arg(staging)
+        .arg(name)
+        .output()
+        .await
+        .map_err(|e| RounalError::BundleError(format!("tar: {}", e)))?;
+
+    if !out.status.success() {
+        return Err(RounalError::BundleError(format!(
+            "tar: {}",
+            String::from_utf8_lossy(&out.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+// The files are gathered in the temp directory and packed into a tarball in
+// the working directory, the staging directory is removed afterwards.
+pub async fn create_bundle(unit: &str, since: &str) -> Result<BundleReport> {
+    info!("create_bundle called: {} since {}", unit, since);
+
+    let name = format!(
+        "rounal-bundle-{}-{}",
+        sanitize_file_name(unit),
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+    // the logs are read with sudo, so the staging directory is a new one
+    // only the user can enter, a path that already exists is not reused
+    let staging = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+    tokio::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&staging)
+        .await
+        .map_err(|e| RounalError::BundleError(format!("{}: {}", staging.display(), e)))?;
+
+    let result = fill_bundle(unit, since, &staging, &name).await;
+
+    if let Err(e) = tokio::fs::remove_dir_all(&staging).await {
+        error!("Removing {} failed: {}", staging.display(), e);
+    }
+    result
+}
+
+async fn fill_bundle(unit: &str, since: &str, staging: &Path, name: &str) -> Result<BundleReport> {
+    let dir = staging.join(name);
+    tokio::fs::create_dir(&dir)
+        .await
+        .map_err(|e| RounalError::BundleError(format!("{}: {}", dir.display(), e)))?;
+
+    let handles: Vec<_> = get_artefacts(unit, since)
+        .into_iter()
+        .map(|artefact| tokio::spawn(collect(dir.clone(), artefact)))
+        .collect();
+
+    let mut artefacts = vec![];
+    for handle in handles {
+        match handle.await {
+            Ok(artefact) => artefacts.push(artefact),
+            Err(e) => error!("Bundle task failed: {}", e),
+        }
+    }
+
+    let failed = artefacts
+        .iter()
+        .filter(|artefact| !artefact["error"].is_null())
+        .count();
+    let manifest = json!({
+        "unit": unit,
+        "since": since,
+        "created": chrono::Local::now().to_rfc3339(),
+        "rounal_version": env!("CARGO_PKG_VERSION"),
+        "artefacts": artefacts,
+    });
+    let manifest = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| RounalError::BundleError(format!("{}: {}", MANIFEST, e)))?;
+    tokio::fs::write(dir.join(MANIFEST), manifest)
+        .await
+        .map_err(|e| RounalError::BundleError(format!("{}: {}", MANIFEST, e)))?;
+
+    let archive = std::env::current_dir()
+        .unwrap_or_default()
+        .join(format!("{}.tar.gz", name));
+    run_tar(&archive, staging, name).await?;
+
+    Ok(BundleReport {
+        path: archive.display().to_string(),
+        collected: artefacts.len() - failed,
+        failed,
+    })
+}
@@ -1,19 +1,290 @@
-use crate::core::error::{Result, RounalError};
-use arboard::Clipboard;
+use crate::core::{
+    config::Options,
+    error::{Result, RounalError},
+    journal::JournalLog,
+};
+use crate::util::{expand_home, map_to_priority_str};
 use log::info;
-use std::thread::sleep;
-use std::time::Duration;
+use std::{io::Write, path::Path, process::Stdio};
+use tokio::{io::AsyncWriteExt, process::Command};
 
-pub fn yank_to_clipboard(content: String) -> Result<()> {
-    let mut clipboard =
-        Clipboard::new().map_err(|e| RounalError::ClipboardError(format!("{:?}", e)))?;
+const INFO_TEMPLATE: &str = "{message}";
+const FULL_TEMPLATE: &str = "{timestamp} {hostname} {unit} [{priority}] {message}";
+const BASE64_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
 
-    info!("Got clipboard: {}", content);
-    yank(&mut clipboard, content.as_str());
-    sleep(Duration::from_secs(1));
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClipboardBackend {
+    // OSC 52 escape sequence, the terminal sets the clipboard, also over ssh
+    Osc52,
+    WlCopy,
+    Xclip,
+    Xsel,
+    Tmux,
+    File(String),
+}
+
+impl ClipboardBackend {
+    // Options::clipboard, checked when the config is loaded, auto picks the
+    // first backend that can work here
+    pub fn from_options(options: &Options) -> Self {
+        match options.clipboard.to_lowercase().as_str() {
+            "osc52" => Self::Osc52,
+            "wl-copy" => Self::WlCopy,
+            "xclip" => Self::Xclip,
+            "xsel" => Self::Xsel,
+            "tmux" => Self::Tmux,
+            "file" => Self::File(options.clipboard_file.clone()),
+            _ => Self::detect(&options.clipboard_file),
+        }
+    }
+
+    // Over ssh only the terminal on the other end has the clipboard, locally a
+    // display server is used when its tool is installed.
+    fn detect(file: &str) -> Self {
+        let is_set = |var: &str| std::env::var_os(var).is_some_and(|value| !value.is_empty());
+
+        if is_set("SSH_TTY") || is_set("SSH_CONNECTION") {
+            Self::Osc52
+        } else if is_set("WAYLAND_DISPLAY") && is_in_path("wl-copy") {
+            Self::WlCopy
+        } else if is_set("DISPLAY") && is_in_path("xclip") {
+            Self::Xclip
+        } else if is_set("DISPLAY") && is_in_path("xsel") {
+            Self::Xsel
+        } else if is_set("TMUX") {
+            Self::Tmux
+        } else if std::env::var("TERM").is_ok_and(|term| term != "linux" && term != "dumb") {
+            Self::Osc52
+        } else {
+            Self::File(file.to_string())
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Osc52 => "terminal (OSC 52)".to_string(),
+            Self::WlCopy => "wl-copy".to_string(),
+            Self::Xclip => "xclip".to_string(),
+            Self::Xsel => "xsel".to_string(),
+            Self::Tmux => "tmux buffer".to_string(),
+            Self::File(path) => path.clone(),
+        }
+    }
+
+    fn get_command(&self) -> Option<(&'static str, &'static [&'static str])> {
+        match self {
+            Self::WlCopy => Some(("wl-copy", &[])),
+            Self::Xclip => Some(("xclip", &["-selection", "clipboard"])),
+            Self::Xsel => Some(("xsel", &["--clipboard", "--input"])),
+            Self::Tmux => Some(("tmux", &["load-buffer", "-"])),
+            Self::Osc52 | Self::File(_) => None,
+        }
+    }
+}
+
+fn is_in_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+}
+
+fn encode_base64(content: &[u8]) -> String {
+    let mut encoded = String::with_capacity(content.len().div_ceil(3) * 4);
+
+    for chunk in content.chunks(3) {
+        let bytes = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let triple = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+
+        for idx in 0..4 {
+            if idx <= chunk.len() {
+                encoded.push(BASE64_CHARS[(triple >> (18 - 6 * idx) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+// tmux passes the sequence on to the outer terminal when it is wrapped
+fn write_osc52(content: &str) -> Result<()> {
+    let sequence = format!("\x1b]52;c;{}\x07", encode_base64(content.as_bytes()));
+    let sequence = if std::env::var_os("TMUX").is_some() {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    };
+
+    let mut stdout = std::io::stdout();
+    stdout
+        .write_all(sequence.as_bytes())
+        .and_then(|_| stdout.flush())
+        .map_err(|e| RounalError::ClipboardError(format!("terminal: {}", e)))
+}
+
+async fn write_file(path: &str, content: &str) -> Result<()> {
+    let path = expand_home(path);
+    if let Some(parent) = Path::new(&path).parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| RounalError::ClipboardError(format!("{}: {}", path, e)))?;
+    }
+    tokio::fs::write(&path, content)
+        .await
+        .map_err(|e| RounalError::ClipboardError(format!("{}: {}", path, e)))
+}
+
+async fn pipe_to_command(program: &str, args: &[&str], content: &str) -> Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| RounalError::ClipboardError(format!("{}: {}", program, e)))?;
+
+    let error = |e: std::io::Error| RounalError::ClipboardError(format!("{}: {}", program, e));
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(content.as_bytes()).await.map_err(error)?;
+    }
+
+    let status = child.wait().await.map_err(error)?;
+    if !status.success() {
+        return Err(RounalError::ClipboardError(format!(
+            "{} exited with {}",
+            program, status
+        )));
+    }
     Ok(())
 }
 
-fn yank(clipboard: &mut Clipboard, content: &str) {
-    let _ = clipboard.set_text(content);
+pub async fn yank_to_clipboard(backend: &ClipboardBackend, content: &str) -> Result<()> {
+    info!("Yank {} bytes to {}", content.len(), backend.name());
+
+    match backend {
+        ClipboardBackend::Osc52 => write_osc52(content),
+        ClipboardBackend::File(path) => write_file(path, content).await,
+        backend => match backend.get_command() {
+            Some((program, args)) => pipe_to_command(program, args, content).await,
+            None => Ok(()),
+        },
+    }
+}
+
+fn get_field(log: &JournalLog, field: &str) -> Option<String> {
+    let value = match field {
+        "timestamp" => log.timestamp.clone(),
+        "priority" => map_to_priority_str(&log.priority).to_string(),
+        "hostname" => log.hostname.clone(),
+        "service" => log.service.clone(),
+        "unit" => log.unit.clone(),
+        "message" => log.log_message.clone(),
+        "invocation" => log.invocation.clone(),
+        "cursor" => log.cursor.clone(),
+        _ => return None,
+    };
+    Some(value)
+}
+
+// Options::yank is info, full or a template like "{timestamp} {message}",
+// unknown placeholders are kept as they are
+pub fn format_yank(log: &JournalLog, yank: &str) -> String {
+    let template = match yank {
+        "info" => INFO_TEMPLATE,
+        "full" => FULL_TEMPLATE,
+        template => template,
+    };
+
+    let mut line = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        line.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+
+        match placeholder
+            .find('}')
+            .and_then(|end| Some((end, get_field(log, &placeholder[1..end])?)))
+        {
+            Some((end, value)) => {
+                line.push_str(&value);
+                rest = &placeholder[end + 1..];
+            }
+            None => {
+                line.push('{');
+                rest = &placeholder[1..];
+            }
+        }
+    }
+    line.push_str(rest);
+    line
+}
+
+pub fn format_yank_lines(entries: &[JournalLog], yank: &str) -> String {
+    entries
+        .iter()
+        .map(|log| format_yank(log, yank))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_base64_with_padding() {
+        assert_eq!(encode_base64(b""), "");
+        assert_eq!(encode_base64(b"f"), "Zg==");
+        assert_eq!(encode_base64(b"fo"), "Zm8=");
+        assert_eq!(encode_base64(b"foo"), "Zm9v");
+        assert_eq!(encode_base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(encode_base64("ä\n".as_bytes()), "w6QK");
+        assert_eq!(encode_base64(&[0xff, 0xfe, 0xfd]), "//79");
+    }
+
+    fn log() -> JournalLog {
+        JournalLog {
+            priority: 4,
+            timestamp: "2024-01-01 10:00:00".to_string(),
+            log_message: "disk almost full".to_string(),
+            hostname: "host".to_string(),
+            service: "nginx".to_string(),
+            unit: "nginx.service".to_string(),
+            cursor: "s=1".to_string(),
+            invocation: "abc".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fills_every_placeholder() {
+        assert_eq!(
+            format_yank(
+                &log(),
+                "{timestamp}|{priority}|{hostname}|{service}|{unit}|{message}|{invocation}|{cursor}"
+            ),
+            "2024-01-01 10:00:00|warn|host|nginx|nginx.service|disk almost full|abc|s=1"
+        );
+    }
+
+    #[test]
+    fn info_and_full_are_templates() {
+        assert_eq!(format_yank(&log(), "info"), "disk almost full");
+        assert_eq!(
+            format_yank(&log(), "full"),
+            "2024-01-01 10:00:00 host nginx.service [warn] disk almost full"
+        );
+    }
+
+    #[test]
+    fn keeps_unknown_placeholders_and_braces() {
+        assert_eq!(format_yank(&log(), "{pid} {unit}"), "{pid} nginx.service");
+        assert_eq!(format_yank(&log(), "{ {message} }"), "{ disk almost full }");
+        assert_eq!(format_yank(&log(), "{unit"), "{unit");
+        assert_eq!(format_yank(&log(), "{{unit}}"), "{nginx.service}");
+    }
 }
@@ -0,0 +1,239 @@
+use crate::core::{
+    config::THEMES,
+    keymap::{find_action, get_action_names, Action},
+    system::UnitAction,
+};
+use crate::util::map_to_priority_str;
+
+// commands of their own, every keymap action is a command by its name too
+const COMMANDS: [&str; 11] = [
+    "unit", "priority", "since", "boot", "jump", "export", "start", "stop", "restart", "set",
+    "theme",
+];
+const SET_OPTIONS: [&str; 4] = ["wrap", "nowrap", "invocations", "noinvocations"];
+const SINCE_EXAMPLES: [&str; 5] = ["-1h", "-24h", "today", "yesterday", "-7d"];
+const BOOT_EXAMPLES: [&str; 3] = ["0", "-1", "-2"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Unit(String),
+    Priority(u8),
+    // none clears the window
+    Since(Option<String>),
+    Boot(Option<String>),
+    // none opens the jump prompt
+    Jump(Option<String>),
+    // none opens the export prompt
+    Export(Option<String>),
+    UnitAction(UnitAction),
+    Set(String, bool),
+    Theme(String),
+    Action(Action),
+}
+
+// Tab in the command line cycles through the candidates of the last word
+#[derive(Debug, Clone)]
+pub struct CommandCompletion {
+    pub typed: String,
+    pub candidates: Vec<String>,
+    pub selected: usize,
+}
+
+fn get_command_names() -> Vec<&'static str> {
+    let mut names: Vec<&str> = COMMANDS
+        .iter()
+        .copied()
+        .chain(get_action_names().filter(|name| *name != "command"))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+// a command is named in full or by a prefix only it has, like :pri
+fn resolve_command(word: &str) -> Result<&'static str, String> {
+    let names = get_command_names();
+    if let Some(name) = names.iter().find(|name| **name == word) {
+        return Ok(name);
+    }
+
+    let matching: Vec<&str> = names
+        .into_iter()
+        .filter(|name| name.starts_with(word))
+        .collect();
+    match matching.as_slice() {
+        [name] => Ok(name),
+        [] => Err(format!("Unknown command: {}", word)),
+        names => Err(format!("Ambiguous command {}: {}", word, names.join(", "))),
+    }
+}
+
+fn parse_priority(arg: &str) -> Option<u8> {
+    match arg.parse::<u8>() {
+        Ok(priority) if (1..=7).contains(&priority) => Some(priority),
+        Ok(_) => None,
+        // emerg is shown with alert, -p 1 is the first bucket
+        Err(_) => (0..=7)
+            .find(|priority| map_to_priority_str(priority) == arg)
+            .map(|priority| priority.max(1)),
+    }
+}
+
+pub fn parse_command(line: &str) -> Result<Command, String> {
+    let line = line.trim();
+    let (word, arg) = match line.split_once(char::is_whitespace) {
+        Some((word, arg)) => (word, arg.trim()),
+        None => (line, ""),
+    };
+    let arg = (!arg.is_empty()).then(|| arg.to_string());
+    let missing = |usage: &str| format!("Usage: :{} {}", word, usage);
+
+    let name = resolve_command(word)?;
+    let command = match name {
+        "unit" => Command::Unit(arg.ok_or_else(|| missing("<name>"))?),
+        "priority" => {
+            let arg = arg.ok_or_else(|| missing("<emerg..debug | 1-7>"))?;
+            Command::Priority(
+                parse_priority(&arg).ok_or_else(|| format!("Unknown priority: {}", arg))?,
+            )
+        }
+        "since" => Command::Since(arg),
+        "boot" => Command::Boot(arg),
+        "jump" => Command::Jump(arg),
+        "export" => Command::Export(arg),
+        "start" => Command::UnitAction(UnitAction::Start),
+        "stop" => Command::UnitAction(UnitAction::Stop),
+        "restart" => Command::UnitAction(UnitAction::Restart),
+        "set" => {
+            let arg = arg.ok_or_else(|| missing(&SET_OPTIONS.join(" | ")))?;
+            match arg.strip_prefix("no") {
+                _ if !SET_OPTIONS.contains(&arg.as_str()) => {
+                    return Err(format!("Unknown option: {}", arg))
+                }
+                Some(option) => Command::Set(option.to_string(), false),
+                None => Command::Set(arg, true),
+            }
+        }
+        "theme" => {
+            let arg = arg.ok_or_else(|| missing(&THEMES.join(" | ")))?;
+            if !THEMES.contains(&arg.as_str()) {
+                return Err(format!("Unknown theme: {}", arg));
+            }
+            Command::Theme(arg)
+        }
+        name => {
+            Command::Action(find_action(name).ok_or_else(|| format!("Unknown command: {}", word))?)
+        }
+    };
+    Ok(command)
+}
+
+// candidates for the word under the cursor, units are offered to :unit
+pub fn get_completions(line: &str, units: &[String]) -> Vec<String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let is_new_word = line.is_empty() || line.ends_with(char::is_whitespace);
+    let word = if is_new_word {
+        ""
+    } else {
+        words.last().copied().unwrap_or_default()
+    };
+    let position = if is_new_word {
+        words.len()
+    } else {
+        words.len().saturating_sub(1)
+    };
+
+    let options: Vec<String> = match position {
+        0 => get_command_names()
+            .into_iter()
+            .map(|name| name.to_string())
+            .collect(),
+        1 => match resolve_command(words[0]) {
+            Ok("unit") => units.to_vec(),
+            Ok("priority") => (1..=7)
+                .map(|priority| map_to_priority_str(&priority).to_string())
+                .collect(),
+            Ok("since") => SINCE_EXAMPLES.iter().map(|s| s.to_string()).collect(),
+            Ok("boot") => BOOT_EXAMPLES.iter().map(|s| s.to_string()).collect(),
+            Ok("set") => SET_OPTIONS.iter().map(|s| s.to_string()).collect(),
+            Ok("theme") => THEMES.iter().map(|s| s.to_string()).collect(),
+            _ => vec![],
+        },
+        _ => vec![],
+    };
+
+    options
+        .into_iter()
+        .filter(|option| option.starts_with(word))
+        .collect()
+}
+
+// the last word of the line replaced by a candidate
+pub fn complete_line(line: &str, candidate: &str) -> String {
+    let start = line
+        .rfind(char::is_whitespace)
+        .map(|idx| idx + 1)
+        .unwrap_or(0);
+    format!("{}{}", &line[..start], candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands_and_arguments() {
+        assert_eq!(
+            parse_command("unit nginx.service"),
+            Ok(Command::Unit("nginx.service".to_string()))
+        );
+        assert_eq!(parse_command(" priority err "), Ok(Command::Priority(3)));
+        assert_eq!(parse_command("priority 6"), Ok(Command::Priority(6)));
+        assert_eq!(
+            parse_command("since -2h"),
+            Ok(Command::Since(Some("-2h".to_string())))
+        );
+        assert_eq!(parse_command("since"), Ok(Command::Since(None)));
+        assert_eq!(
+            parse_command("restart"),
+            Ok(Command::UnitAction(UnitAction::Restart))
+        );
+        assert_eq!(
+            parse_command("set nowrap"),
+            Ok(Command::Set("wrap".to_string(), false))
+        );
+        assert_eq!(
+            parse_command("theme light"),
+            Ok(Command::Theme("light".to_string()))
+        );
+        assert_eq!(
+            parse_command("histogram"),
+            Ok(Command::Action(Action::Histogram))
+        );
+    }
+
+    #[test]
+    fn resolves_unique_prefixes() {
+        assert_eq!(parse_command("pri 1"), Ok(Command::Priority(1)));
+        assert!(parse_command("s")
+            .unwrap_err()
+            .starts_with("Ambiguous command s"));
+    }
+
+    #[test]
+    fn reports_bad_input() {
+        assert_eq!(
+            parse_command("nonsense"),
+            Err("Unknown command: nonsense".to_string())
+        );
+        assert_eq!(
+            parse_command("priority 9"),
+            Err("Unknown priority: 9".to_string())
+        );
+        assert!(parse_command("unit")
+            .unwrap_err()
+            .starts_with("Usage: :unit"));
+        assert!(parse_command("set color").is_err());
+        assert!(parse_command("theme blue").is_err());
+    }
+}
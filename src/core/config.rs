@@ -1,8 +1,11 @@
-use crate::core::error::{Result, RounalError};
+use crate::core::{
+    error::{Result, RounalError},
+    keymap::{KeyBinding, Keymap},
+};
 use log::{error, LevelFilter};
-use ratatui::style::Color;
+use ratatui::{layout::Direction, style::Color};
 use serde::Deserialize;
-use std::{fs, path::Path};
+use std::{collections::HashMap, fs, path::Path};
 use toml;
 
 #[derive(Debug, Deserialize, Clone)]
@@ -20,6 +23,8 @@ pub struct Palette {
 pub struct Priority {
     pub emerg: [u8; 3],
     pub alert: [u8; 3],
+    #[serde(default = "default_crit")]
+    pub crit: [u8; 3],
     pub err: [u8; 3],
     pub warn: [u8; 3],
     pub notice: [u8; 3],
@@ -28,6 +33,9 @@ pub struct Priority {
     pub unknown: [u8; 3],
 }
 
+pub const THEMES: [&str; 3] = ["config", "dark", "light"];
+pub const CLIPBOARDS: [&str; 7] = ["auto", "osc52", "wl-copy", "xclip", "xsel", "tmux", "file"];
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct Options {
     pub description: bool,
@@ -37,6 +45,65 @@ pub struct Options {
     pub command_format: String,
     pub cursor_left: String,
     pub cursor_right: String,
+    #[serde(default = "default_search_since")]
+    pub search_since: String,
+    #[serde(default)]
+    pub unit_filter: Vec<String>,
+    #[serde(default = "default_layout")]
+    pub layout: String,
+    #[serde(default = "default_split_ratio")]
+    pub split_ratio: u16,
+    #[serde(default = "default_histogram_height")]
+    pub histogram_height: u16,
+    #[serde(default = "default_clipboard")]
+    pub clipboard: String,
+    #[serde(default = "default_clipboard_file")]
+    pub clipboard_file: String,
+    #[serde(default = "default_bookmarks_file")]
+    pub bookmarks_file: String,
+    #[serde(default = "default_marks_file")]
+    pub marks_file: String,
+    #[serde(default)]
+    pub mouse: bool,
+    #[serde(default)]
+    pub group_invocations: bool,
+}
+
+// older config files have no crit, it sits between alert and err
+fn default_crit() -> [u8; 3] {
+    [202, 45, 14]
+}
+
+fn default_search_since() -> String {
+    "-24h".to_string()
+}
+
+fn default_layout() -> String {
+    "single".to_string()
+}
+
+fn default_split_ratio() -> u16 {
+    50
+}
+
+fn default_histogram_height() -> u16 {
+    6
+}
+
+fn default_clipboard() -> String {
+    "auto".to_string()
+}
+
+fn default_clipboard_file() -> String {
+    "~/.cache/rounal/clipboard".to_string()
+}
+
+fn default_bookmarks_file() -> String {
+    "~/.local/share/rounal/bookmarks.json".to_string()
+}
+
+fn default_marks_file() -> String {
+    "~/.local/share/rounal/marks.json".to_string()
 }
 
 impl Options {
@@ -50,6 +117,26 @@ impl Options {
             _ => LevelFilter::Off,
         }
     }
+
+    // horizontal puts the panes side by side, vertical stacks them
+    pub fn get_split_direction(&self) -> Option<Direction> {
+        match self.layout.to_lowercase().as_str() {
+            "horizontal" => Some(Direction::Horizontal),
+            "vertical" => Some(Direction::Vertical),
+            _ => None,
+        }
+    }
+}
+
+// column names and order of the tables, empty uses the defaults
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct Columns {
+    #[serde(default)]
+    pub units: Vec<String>,
+    #[serde(default)]
+    pub files: Vec<String>,
+    #[serde(default)]
+    pub logs: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -57,6 +144,16 @@ pub struct Config {
     pub palette: Palette,
     pub priority: Priority,
     pub options: Options,
+    #[serde(default)]
+    pub columns: Columns,
+    // action name to one key or a list of keys, see keymap.rs
+    #[serde(default)]
+    pub keys: HashMap<String, KeyBinding>,
+    #[serde(skip)]
+    pub keymap: Keymap,
+    // the colors of the config file while a theme is on
+    #[serde(skip)]
+    config_colors: Option<(Palette, Priority)>,
 }
 
 impl Config {
@@ -73,14 +170,89 @@ impl Config {
             RounalError::ConfigurationFileError
         })?;
 
-        let config: Config = toml::from_str(&contents).map_err(|e| {
+        let mut config: Config = toml::from_str(&contents).map_err(|e| {
             error!("Failed to parse config: {}", e);
             RounalError::ConfigurationFileError
         })?;
 
+        config.keymap = Keymap::new(&config.keys).inspect_err(|e| {
+            error!("Invalid [keys] section: {}", e);
+        })?;
+
+        let clipboard = config.options.clipboard.to_lowercase();
+        if !CLIPBOARDS.contains(&clipboard.as_str()) {
+            return Err(RounalError::ClipboardError(format!(
+                "unknown clipboard {}, expected {}",
+                config.options.clipboard,
+                CLIPBOARDS.join(" | ")
+            )));
+        }
+
         Ok(config.clone())
     }
 
+    // dark is the GitHub Dark Default of app_config.toml, light its light
+    // counterpart, black is the background and white the text, config goes
+    // back to the colors of the config file
+    pub fn set_theme(&mut self, theme: &str) -> bool {
+        let config_colors = self
+            .config_colors
+            .get_or_insert_with(|| (self.palette.clone(), self.priority.clone()));
+
+        let (palette, priority) = match theme {
+            "config" => config_colors.clone(),
+            "dark" => (
+                Palette {
+                    red: [248, 81, 73],
+                    black: [13, 17, 23],
+                    blue: [56, 139, 253],
+                    white: [201, 209, 217],
+                    gray: [110, 118, 129],
+                    green: [63, 185, 80],
+                    yellow: [202, 171, 0],
+                },
+                Priority {
+                    emerg: [211, 10, 39],
+                    alert: [198, 19, 22],
+                    crit: [202, 45, 14],
+                    err: [206, 70, 6],
+                    warn: [235, 82, 5],
+                    notice: [255, 251, 0],
+                    info: [35, 217, 35],
+                    debug: [0, 122, 255],
+                    unknown: [255, 255, 255],
+                },
+            ),
+            "light" => (
+                Palette {
+                    red: [207, 34, 46],
+                    black: [255, 255, 255],
+                    blue: [9, 105, 218],
+                    white: [31, 35, 40],
+                    gray: [101, 109, 118],
+                    green: [26, 127, 55],
+                    yellow: [154, 103, 0],
+                },
+                Priority {
+                    emerg: [164, 14, 38],
+                    alert: [207, 34, 46],
+                    crit: [198, 55, 23],
+                    err: [188, 76, 0],
+                    warn: [154, 103, 0],
+                    notice: [125, 78, 0],
+                    info: [26, 127, 55],
+                    debug: [9, 105, 218],
+                    unknown: [31, 35, 40],
+                },
+            ),
+            _ => return false,
+        };
+
+        self.palette = palette;
+        self.priority = priority;
+        true
+    }
+
     pub fn get_palette_color(&self, color_name: &str) -> Color {
         match color_name {
             "red" => Color::Rgb(
@@ -134,6 +306,11 @@ impl Config {
                 self.priority.alert[1],
                 self.priority.alert[2],
             ),
+            "crit" => Color::Rgb(
+                self.priority.crit[0],
+                self.priority.crit[1],
+                self.priority.crit[2],
+            ),
             "err" => Color::Rgb(
                 self.priority.err[0],
                 self.priority.err[1],
@@ -0,0 +1,205 @@
+use crate::core::{
+    journal::{
+        format_timestamp, get_disk_usage, get_error_counts, get_last_error, get_restarts,
+        ENTIRE_JOURNAL,
+    },
+    system::{Active, ServiceUnits, Sub},
+};
+use log::error;
+
+const ERRORS_SINCE: &str = "-1h";
+const RESTARTS_SINCE: &str = "-24h";
+const SECTION_LIMIT: usize = 10;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DashboardLink {
+    // the logs of a unit at a priority
+    Logs { unit: String, priority: u8 },
+    // the unit list with a single filter
+    Units(&'static str),
+}
+
+#[derive(Debug, Clone)]
+pub struct DashboardEntry {
+    pub label: String,
+    pub detail: String,
+    pub link: DashboardLink,
+}
+
+#[derive(Debug, Clone)]
+pub struct DashboardSection {
+    pub title: &'static str,
+    pub entries: Vec<DashboardEntry>,
+}
+
+#[derive(Debug)]
+pub struct Dashboard {
+    pub sections: Vec<DashboardSection>,
+    pub errors: Vec<String>,
+    pub current_line: usize,
+}
+
+impl Dashboard {
+    pub fn get_entries(&self) -> impl Iterator<Item = &DashboardEntry> {
+        self.sections
+            .iter()
+            .flat_map(|section| section.entries.iter())
+    }
+
+    pub fn get_selected(&self) -> Option<&DashboardEntry> {
+        self.get_entries().nth(self.current_line)
+    }
+
+    pub fn move_cursor_down(&mut self) {
+        if self.current_line < self.get_entries().count().saturating_sub(1) {
+            self.current_line += 1;
+        }
+    }
+
+    pub fn move_cursor_up(&mut self) {
+        if self.current_line > 0 {
+            self.current_line -= 1;
+        }
+    }
+}
+
+fn logs_entry(unit: &str, priority: u8, detail: String) -> DashboardEntry {
+    DashboardEntry {
+        label: unit.to_string(),
+        detail,
+        link: DashboardLink::Logs {
+            unit: unit.to_string(),
+            priority,
+        },
+    }
+}
+
+// A query that fails leaves its section empty and is reported on the
+// dashboard, the rest of it is still shown.
+pub async fn load_dashboard(units: &[ServiceUnits], initial_priority: u8) -> Dashboard {
+    let mut errors = vec![];
+
+    let count = |filter: fn(&ServiceUnits) -> bool| units.iter().filter(|u| filter(u)).count();
+    let states = DashboardSection {
+        title: "Units",
+        entries: vec![
+            DashboardEntry {
+                label: "running".to_string(),
+                detail: count(|u| u.sub == Sub::Running).to_string(),
+                link: DashboardLink::Units("sub=running"),
+            },
+            DashboardEntry {
+                label: "failed".to_string(),
+                detail: count(|u| u.sub == Sub::Failed).to_string(),
+                link: DashboardLink::Units("sub=failed"),
+            },
+            DashboardEntry {
+                label: "inactive".to_string(),
+                detail: count(|u| u.active == Active::InActive).to_string(),
+                link: DashboardLink::Units("active=inactive"),
+            },
+        ],
+    };
+
+    let (error_counts, restarts, disk_usage) = tokio::join!(
+        get_error_counts(ERRORS_SINCE),
+        get_restarts(RESTARTS_SINCE),
+        get_disk_usage()
+    );
+
+    let top_errors = DashboardSection {
+        title: "Most errors in the last hour",
+        entries: error_counts
+            .unwrap_or_else(|e| {
+                errors.push(e.to_string());
+                vec![]
+            })
+            .into_iter()
+            .take(SECTION_LIMIT)
+            .map(|(unit, count)| logs_entry(&unit, 3, format!("{} errors", count)))
+            .collect(),
+    };
+
+    let restarts = DashboardSection {
+        title: "Restarts in the last day",
+        entries: restarts
+            .unwrap_or_else(|e| {
+                errors.push(e.to_string());
+                vec![]
+            })
+            .into_iter()
+            .take(SECTION_LIMIT)
+            .map(|restart| {
+                logs_entry(
+                    &restart.unit,
+                    initial_priority,
+                    format!(
+                        "{}x, last {}",
+                        restart.count,
+                        format_timestamp(restart.realtime)
+                    ),
+                )
+            })
+            .collect(),
+    };
+
+    let failed_units: Vec<String> = units
+        .iter()
+        .filter(|u| u.sub == Sub::Failed)
+        .take(SECTION_LIMIT)
+        .map(|u| u.name.clone())
+        .collect();
+    let handles: Vec<_> = failed_units
+        .iter()
+        .map(|unit| {
+            let unit = unit.clone();
+            tokio::spawn(async move { get_last_error(&unit).await })
+        })
+        .collect();
+
+    let mut failed = DashboardSection {
+        title: "Failed units",
+        entries: vec![],
+    };
+    for (unit, handle) in failed_units.iter().zip(handles) {
+        let last_error = match handle.await {
+            Ok(Ok(log)) => log
+                .map(|log| format!("{} {}", log.timestamp, log.log_message))
+                .unwrap_or_default(),
+            Ok(Err(e)) => {
+                error!("Last error of {} failed: {}", unit, e);
+                "".to_string()
+            }
+            Err(e) => {
+                error!("Last error task of {} failed: {}", unit, e);
+                "".to_string()
+            }
+        };
+        failed.entries.push(logs_entry(unit, 3, last_error));
+    }
+
+    let journal = DashboardSection {
+        title: "Journal",
+        entries: vec![DashboardEntry {
+            label: "disk usage".to_string(),
+            detail: disk_usage.unwrap_or_else(|e| {
+                errors.push(e.to_string());
+                "unknown".to_string()
+            }),
+            link: DashboardLink::Logs {
+                unit: ENTIRE_JOURNAL.to_string(),
+                priority: initial_priority,
+            },
+        }],
+    };
+
+    for e in &errors {
+        error!("Dashboard: {}", e);
+    }
+
+    Dashboard {
+        sections: vec![states, top_errors, restarts, failed, journal],
+        errors,
+        current_line: 0,
+    }
+}
@@ -0,0 +1,157 @@
+use crate::core::{journal::JournalLog, patterns::mask_message};
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DedupeMode {
+    #[default]
+    Off,
+    Identical,
+    Template,
+}
+
+impl DedupeMode {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Off => Self::Identical,
+            Self::Identical => Self::Template,
+            Self::Template => Self::Off,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Identical => "identical",
+            Self::Template => "template",
+        }
+    }
+
+    fn get_key(&self, log: &JournalLog) -> Option<String> {
+        match self {
+            Self::Off => None,
+            Self::Identical => Some(log.log_message.clone()),
+            Self::Template => Some(mask_message(&log.log_message)),
+        }
+    }
+}
+
+// consecutive entries with the same message, shown as the first of them
+#[derive(Debug, Clone)]
+pub struct DuplicateRun {
+    pub first_line: usize,
+    pub count: usize,
+    // the journal cursor of the first entry, kept over reloads
+    pub cursor: String,
+    pub start: u64,
+    pub end: u64,
+}
+
+impl DuplicateRun {
+    pub fn contains(&self, line: usize) -> bool {
+        (self.first_line..self.first_line + self.count).contains(&line)
+    }
+}
+
+// Runs stop at a change of unit or invocation, so they never hide the
+// first entry of an invocation group.
+pub fn find_duplicate_runs(entries: &[JournalLog], mode: DedupeMode) -> Vec<DuplicateRun> {
+    let mut runs: Vec<DuplicateRun> = vec![];
+    let mut previous: Option<(String, &JournalLog)> = None;
+
+    for (idx, log) in entries.iter().enumerate() {
+        let Some(key) = mode.get_key(log) else {
+            return runs;
+        };
+
+        let is_repeated = previous.as_ref().is_some_and(|(prev_key, prev)| {
+            *prev_key == key && prev.unit == log.unit && prev.invocation == log.invocation
+        });
+
+        if is_repeated {
+            match runs.last_mut() {
+                Some(run) if run.contains(idx - 1) => {
+                    run.count += 1;
+                    run.start = run.start.min(log.realtime);
+                    run.end = run.end.max(log.realtime);
+                }
+                _ => {
+                    let prev = previous.as_ref().map(|(_, prev)| *prev).unwrap_or(log);
+                    runs.push(DuplicateRun {
+                        first_line: idx - 1,
+                        count: 2,
+                        cursor: prev.cursor.clone(),
+                        start: prev.realtime.min(log.realtime),
+                        end: prev.realtime.max(log.realtime),
+                    });
+                }
+            }
+        }
+
+        previous = Some((key, log));
+    }
+
+    runs
+}
+
+// every line of a collapsed run but its first
+pub fn get_hidden_lines(runs: &[DuplicateRun]) -> HashSet<usize> {
+    runs.iter()
+        .flat_map(|run| run.first_line + 1..run.first_line + run.count)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(message: &str, unit: &str, realtime: u64) -> JournalLog {
+        JournalLog {
+            log_message: message.to_string(),
+            unit: unit.to_string(),
+            cursor: format!("c{}", realtime),
+            realtime,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn finds_runs_of_identical_messages() {
+        let entries = [
+            log("a", "x", 5),
+            log("b", "x", 4),
+            log("b", "x", 3),
+            log("b", "x", 2),
+            log("a", "x", 1),
+        ];
+        let runs = find_duplicate_runs(&entries, DedupeMode::Identical);
+
+        assert_eq!(runs.len(), 1);
+        assert_eq!((runs[0].first_line, runs[0].count), (1, 3));
+        assert_eq!((runs[0].start, runs[0].end), (2, 4));
+        assert_eq!(runs[0].cursor, "c4");
+        assert_eq!(get_hidden_lines(&runs), HashSet::from([2, 3]));
+    }
+
+    #[test]
+    fn templates_join_messages_that_differ_in_masked_tokens() {
+        let entries = [log("took 12ms", "x", 2), log("took 30ms", "x", 1)];
+
+        assert!(find_duplicate_runs(&entries, DedupeMode::Identical).is_empty());
+        assert_eq!(find_duplicate_runs(&entries, DedupeMode::Template).len(), 1);
+    }
+
+    #[test]
+    fn runs_stop_at_a_change_of_unit() {
+        let entries = [log("a", "x", 3), log("a", "y", 2), log("a", "y", 1)];
+        let runs = find_duplicate_runs(&entries, DedupeMode::Identical);
+
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].first_line, 1);
+    }
+
+    #[test]
+    fn off_finds_nothing() {
+        let entries = [log("a", "x", 2), log("a", "x", 1)];
+        assert!(find_duplicate_runs(&entries, DedupeMode::Off).is_empty());
+    }
+}
@@ -22,6 +22,24 @@ pub enum RounalError {
     #[error("Config file error")]
     ConfigurationFileError,
 
+    #[error("Key binding error: {0}")]
+    KeymapError(String),
+
     #[error("Copying to clipboard error: {0}")]
     ClipboardError(String),
+
+    #[error("Export failed: {0}")]
+    ExportError(String),
+
+    #[error("{0} already exists")]
+    ExportFileExists(String),
+
+    #[error("Diagnostic bundle failed: {0}")]
+    BundleError(String),
+
+    #[error("Bookmarks error: {0}")]
+    BookmarkError(String),
+
+    #[error("Marks error: {0}")]
+    MarkError(String),
 }
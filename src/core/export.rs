@@ -0,0 +1,310 @@
+use crate::{
+    core::{
+        bookmarks::{find_bookmark, Bookmark},
+        config::Config,
+        error::{Result, RounalError},
+        journal::JournalLog,
+    },
+    util::{expand_home, map_to_priority_str, sanitize_file_name},
+};
+use ratatui::style::Color;
+use serde_json::json;
+use std::{io::ErrorKind, path::Path};
+use tokio::{fs::OpenOptions, io::AsyncWriteExt};
+
+const CSV_HEADER: &str = "timestamp,priority,hostname,service,unit,message,bookmarked,note";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Text,
+    JsonLines,
+    Csv,
+    Html,
+}
+
+impl ExportFormat {
+    // unknown extensions are written as plain text
+    pub fn from_path(path: &str) -> Self {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+
+        match extension.as_str() {
+            "json" | "jsonl" => Self::JsonLines,
+            "csv" => Self::Csv,
+            "html" | "htm" => Self::Html,
+            _ => Self::Text,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Text => "log",
+            Self::JsonLines => "jsonl",
+            Self::Csv => "csv",
+            Self::Html => "html",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Text => Self::JsonLines,
+            Self::JsonLines => Self::Csv,
+            Self::Csv => Self::Html,
+            Self::Html => Self::Text,
+        }
+    }
+}
+
+// Tab in the prompt, the path keeps its name with the next format's extension
+pub fn cycle_export_extension(path: &str) -> String {
+    let format = ExportFormat::from_path(path).next();
+    Path::new(path)
+        .with_extension(format.extension())
+        .to_string_lossy()
+        .to_string()
+}
+
+// a file name in the working directory
+pub fn default_export_path(service: &str) -> String {
+    format!(
+        "{}-{}.{}",
+        sanitize_file_name(service),
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        ExportFormat::Text.extension()
+    )
+}
+
+// bookmarked lines end with the marker and their note
+fn to_text(entries: &[JournalLog], bookmarks: &[Bookmark]) -> String {
+    entries
+        .iter()
+        .map(|log| {
+            let bookmark = match find_bookmark(bookmarks, &log.cursor) {
+                Some(bookmark) if bookmark.note.is_empty() => {
+                    format!("  {}", bookmark.get_marker())
+                }
+                Some(bookmark) => format!("  {} {}", bookmark.get_marker(), bookmark.note),
+                None => "".to_string(),
+            };
+            format!(
+                "{} {} {}: {}{}\n",
+                log.timestamp, log.hostname, log.service, log.log_message, bookmark
+            )
+        })
+        .collect()
+}
+
+fn to_json_lines(entries: &[JournalLog], bookmarks: &[Bookmark]) -> String {
+    entries
+        .iter()
+        .map(|log| {
+            let bookmark = find_bookmark(bookmarks, &log.cursor);
+            let line = json!({
+                "timestamp": log.timestamp,
+                "realtime": log.realtime,
+                "priority": log.priority,
+                "hostname": log.hostname,
+                "service": log.service,
+                "unit": log.unit,
+                "message": log.log_message,
+                "invocation": log.invocation,
+                "cursor": log.cursor,
+                "bookmarked": bookmark.is_some(),
+                "note": bookmark.map(|bookmark| bookmark.note.as_str()).unwrap_or_default(),
+            });
+            format!("{}\n", line)
+        })
+        .collect()
+}
+
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn to_csv(entries: &[JournalLog], bookmarks: &[Bookmark]) -> String {
+    let mut csv = format!("{}\n", CSV_HEADER);
+    for log in entries {
+        let bookmark = find_bookmark(bookmarks, &log.cursor);
+        let fields = [
+            log.timestamp.as_str(),
+            map_to_priority_str(&log.priority),
+            log.hostname.as_str(),
+            log.service.as_str(),
+            log.unit.as_str(),
+            log.log_message.as_str(),
+            if bookmark.is_some() { "true" } else { "false" },
+            bookmark
+                .map(|bookmark| bookmark.note.as_str())
+                .unwrap_or_default(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|field| escape_csv(field)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn to_css(color: Color) -> String {
+    match color {
+        Color::Rgb(r, g, b) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        _ => "inherit".to_string(),
+    }
+}
+
+// a single file with inline styles in the colours of the config,
+// bookmarked rows are highlighted with their note in the last column
+fn to_html(entries: &[JournalLog], bookmarks: &[Bookmark], title: &str, config: &Config) -> String {
+    let rows: String = entries
+        .iter()
+        .map(|log| {
+            let priority_str = map_to_priority_str(&log.priority);
+            let bookmark = find_bookmark(bookmarks, &log.cursor);
+            format!(
+                "<tr{}><td>{}</td><td style=\"color: {}\">{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                if bookmark.is_some() { " class=\"bookmark\"" } else { "" },
+                escape_html(&log.timestamp),
+                to_css(config.get_priority_color(priority_str)),
+                priority_str,
+                escape_html(&log.hostname),
+                escape_html(&log.service),
+                escape_html(&log.log_message),
+                bookmark
+                    .map(|bookmark| format!("{} {}", bookmark.get_marker(), escape_html(&bookmark.note)))
+                    .unwrap_or_default(),
+            )
+        })
+        .collect();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ background: {background}; color: {foreground}; font-family: monospace; }}
+table {{ border-collapse: collapse; }}
+th {{ color: {header}; text-align: left; }}
+td, th {{ padding: 0 1em 0 0; vertical-align: top; white-space: pre-wrap; }}
+tr.bookmark td {{ color: {header}; }}
+</style>
+</head>
+<body>
+<h2>{title}</h2>
+<p>{count} entries, exported {exported}</p>
+<table>
+<tr><th>TIME</th><th>PRIORITY</th><th>HOST</th><th>SERVICE</th><th>MESSAGE</th><th>NOTE</th></tr>
+{rows}</table>
+</body>
+</html>
+"#,
+        title = escape_html(title),
+        background = to_css(config.get_palette_color("black")),
+        foreground = to_css(config.get_palette_color("white")),
+        header = to_css(config.get_palette_color("yellow")),
+        count = entries.len(),
+        exported = chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+        rows = rows,
+    )
+}
+
+// The format follows the extension of the path, returns the entries written.
+// An existing file is only written over when asked to.
+pub async fn export_logs(
+    path: &str,
+    entries: &[JournalLog],
+    bookmarks: &[Bookmark],
+    title: &str,
+    config: &Config,
+    overwrite: bool,
+) -> Result<usize> {
+    if path.trim().is_empty() {
+        return Err(RounalError::ExportError("no file name given".to_string()));
+    }
+
+    let content = match ExportFormat::from_path(path) {
+        ExportFormat::Text => to_text(entries, bookmarks),
+        ExportFormat::JsonLines => to_json_lines(entries, bookmarks),
+        ExportFormat::Csv => to_csv(entries, bookmarks),
+        ExportFormat::Html => to_html(entries, bookmarks, title, config),
+    };
+
+    let mut options = OpenOptions::new();
+    options.write(true);
+    if overwrite {
+        options.create(true).truncate(true);
+    } else {
+        options.create_new(true);
+    }
+
+    let mut file = options
+        .open(expand_home(path))
+        .await
+        .map_err(|e| match e.kind() {
+            ErrorKind::AlreadyExists => RounalError::ExportFileExists(path.to_string()),
+            _ => RounalError::ExportError(format!("{}: {}", path, e)),
+        })?;
+    file.write_all(content.as_bytes())
+        .await
+        .map_err(|e| RounalError::ExportError(format!("{}: {}", path, e)))?;
+
+    Ok(entries.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_csv_fields() {
+        assert_eq!(escape_csv("plain"), "plain");
+        assert_eq!(escape_csv("a,b"), "\"a,b\"");
+        assert_eq!(escape_csv("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape_csv("one\ntwo"), "\"one\ntwo\"");
+    }
+
+    #[test]
+    fn csv_rows_keep_their_columns() {
+        let log = JournalLog {
+            priority: 3,
+            timestamp: "2024-01-01 10:00:00".to_string(),
+            log_message: "failed, \"disk\"\nretrying".to_string(),
+            hostname: "host".to_string(),
+            service: "nginx".to_string(),
+            unit: "nginx.service".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            to_csv(&[log], &[]),
+            format!(
+                "{}\n2024-01-01 10:00:00,err,host,nginx,nginx.service,\"failed, \"\"disk\"\"\nretrying\",false,\n",
+                CSV_HEADER
+            )
+        );
+    }
+
+    #[test]
+    fn escapes_html() {
+        assert_eq!(
+            escape_html("<a href=\"x\">&</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+        // & goes first, the entities it adds are not escaped again
+        assert_eq!(escape_html("&lt;"), "&amp;lt;");
+    }
+}
@@ -0,0 +1,192 @@
+use crate::core::system::{Active, Load, ServiceUnits, Sub};
+use log::error;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterField {
+    Sub,
+    Active,
+    Load,
+}
+
+// "sub=failed" shows only failed units, "!load=not-found" hides the
+// units that were not found
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitFilter {
+    pub field: FilterField,
+    pub value: String,
+    pub negate: bool,
+}
+
+// hotkey, filter
+pub const QUICK_FILTERS: [(char, &str); 8] = [
+    ('r', "sub=running"),
+    ('x', "sub=failed"),
+    ('e', "sub=exited"),
+    ('d', "sub=dead"),
+    ('a', "active=active"),
+    ('i', "active=inactive"),
+    ('n', "!load=not-found"),
+    ('N', "load=not-found"),
+];
+
+impl UnitFilter {
+    pub fn parse(filter: &str) -> Option<Self> {
+        let (negate, filter) = match filter.trim().strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, filter.trim()),
+        };
+        let (field, value) = filter.split_once('=')?;
+
+        let field = match field.trim() {
+            "sub" => FilterField::Sub,
+            "active" => FilterField::Active,
+            "load" => FilterField::Load,
+            _ => return None,
+        };
+
+        // a misspelled state would match the units of unknown state instead
+        let value = value.trim();
+        let is_known = match field {
+            FilterField::Sub => Sub::get_sub_state(value) != Sub::Unknown,
+            FilterField::Active => Active::get_active_state(value) != Active::Unknown,
+            FilterField::Load => Load::get_load_state(value) != Load::Unknown,
+        };
+        if !is_known {
+            return None;
+        }
+
+        Some(Self {
+            field,
+            value: value.to_string(),
+            negate,
+        })
+    }
+
+    // the filters that parse and the ones that do not, to be reported
+    pub fn parse_all(filters: &[String]) -> (Vec<Self>, Vec<String>) {
+        let mut parsed = vec![];
+        let mut invalid = vec![];
+        for filter in filters {
+            match Self::parse(filter) {
+                Some(filter) => parsed.push(filter),
+                None => {
+                    error!("Invalid unit filter in config: {}", filter);
+                    invalid.push(filter.clone());
+                }
+            }
+        }
+        (parsed, invalid)
+    }
+
+    fn is_match(&self, unit: &ServiceUnits) -> bool {
+        match self.field {
+            FilterField::Sub => unit.sub == Sub::get_sub_state(&self.value),
+            FilterField::Active => unit.active == Active::get_active_state(&self.value),
+            FilterField::Load => unit.load == Load::get_load_state(&self.value),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        let field = match self.field {
+            FilterField::Sub => "sub",
+            FilterField::Active => "active",
+            FilterField::Load => "load",
+        };
+
+        format!(
+            "{}{}={}",
+            if self.negate { "!" } else { "" },
+            field,
+            self.value
+        )
+    }
+}
+
+// Filters on the same field are alternatives (running or failed), filters
+// on different fields must all hold, negated filters always exclude.
+pub fn is_unit_visible(filters: &[UnitFilter], unit: &ServiceUnits) -> bool {
+    let fields = [FilterField::Sub, FilterField::Active, FilterField::Load];
+
+    fields.iter().all(|field| {
+        let (hide, show): (Vec<&UnitFilter>, Vec<&UnitFilter>) = filters
+            .iter()
+            .filter(|f| &f.field == field)
+            .partition(|f| f.negate);
+
+        let is_hidden = hide.iter().any(|f| f.is_match(unit));
+        let is_shown = show.is_empty() || show.iter().any(|f| f.is_match(unit));
+
+        !is_hidden && is_shown
+    })
+}
+
+pub fn toggle_filter(filters: &mut Vec<UnitFilter>, filter: UnitFilter) {
+    if let Some(idx) = filters.iter().position(|f| f == &filter) {
+        filters.remove(idx);
+    } else {
+        filters.push(filter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(field: FilterField, value: &str, negate: bool) -> Option<UnitFilter> {
+        Some(UnitFilter {
+            field,
+            value: value.to_string(),
+            negate,
+        })
+    }
+
+    #[test]
+    fn parses_fields_and_negation() {
+        assert_eq!(
+            UnitFilter::parse("sub=running"),
+            filter(FilterField::Sub, "running", false)
+        );
+        assert_eq!(
+            UnitFilter::parse(" !load = not-found "),
+            filter(FilterField::Load, "not-found", true)
+        );
+        assert_eq!(
+            UnitFilter::parse("active=inactive"),
+            filter(FilterField::Active, "inactive", false)
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_fields_and_states() {
+        for input in [
+            "",
+            "running",
+            "state=running",
+            "sub=runing",
+            "active=",
+            "load=!",
+        ] {
+            assert_eq!(UnitFilter::parse(input), None, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn labels_parse_back() {
+        for (_, quick) in QUICK_FILTERS {
+            let parsed = UnitFilter::parse(quick).unwrap();
+            assert_eq!(parsed.label(), quick);
+        }
+    }
+
+    #[test]
+    fn parse_all_keeps_the_invalid_ones() {
+        let filters = ["sub=failed".to_string(), "sub=nope".to_string()];
+        let (parsed, invalid) = UnitFilter::parse_all(&filters);
+
+        assert_eq!(
+            parsed,
+            vec![filter(FilterField::Sub, "failed", false).unwrap()]
+        );
+        assert_eq!(invalid, vec!["sub=nope".to_string()]);
+    }
+}
@@ -0,0 +1,271 @@
+use crate::core::system::{ServiceUnitFiles, ServiceUnits};
+use std::collections::HashSet;
+
+const SCORE_MATCH: i64 = 16;
+const BONUS_BOUNDARY: i64 = 10;
+const BONUS_FIRST_CHAR: i64 = 8;
+const BONUS_CONSECUTIVE: i64 = 12;
+const PENALTY_GAP: i64 = 1;
+
+#[derive(Debug, Clone)]
+pub struct FuzzyCandidate {
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct FuzzyResult {
+    pub candidate: usize,
+    pub score: i64,
+    // char positions in "name description"
+    pub positions: Vec<usize>,
+}
+
+#[derive(Debug)]
+pub struct FuzzyFinder {
+    pub query: String,
+    pub candidates: Vec<FuzzyCandidate>,
+    pub results: Vec<FuzzyResult>,
+    pub current_line: usize,
+}
+
+impl FuzzyFinder {
+    pub fn new(units: &[ServiceUnits], files: &[ServiceUnitFiles]) -> Self {
+        let mut seen = HashSet::new();
+        let mut candidates = vec![];
+
+        for unit in units {
+            if seen.insert(unit.name.clone()) {
+                candidates.push(FuzzyCandidate {
+                    name: unit.name.clone(),
+                    description: unit.description.clone(),
+                });
+            }
+        }
+
+        // unit files are only listed when no loaded unit has the same name
+        for file in files {
+            if seen.insert(file.name.clone()) {
+                candidates.push(FuzzyCandidate {
+                    name: file.name.clone(),
+                    description: "".to_string(),
+                });
+            }
+        }
+
+        let mut finder = Self {
+            query: "".to_string(),
+            candidates,
+            results: vec![],
+            current_line: 0,
+        };
+        finder.update_results();
+        finder
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        self.query.push(c);
+        self.update_results();
+    }
+
+    pub fn pop_char(&mut self) {
+        self.query.pop();
+        self.update_results();
+    }
+
+    fn update_results(&mut self) {
+        let mut results: Vec<FuzzyResult> = self
+            .candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, candidate)| {
+                let haystack = format!("{} {}", candidate.name, candidate.description);
+                fuzzy_match(&self.query, &haystack).map(|(score, positions)| FuzzyResult {
+                    candidate: idx,
+                    score,
+                    positions,
+                })
+            })
+            .collect();
+
+        // shorter names win ties, the same way fzf prefers tighter matches
+        results.sort_by(|a, b| {
+            b.score.cmp(&a.score).then_with(|| {
+                self.candidates[a.candidate]
+                    .name
+                    .len()
+                    .cmp(&self.candidates[b.candidate].name.len())
+            })
+        });
+
+        self.results = results;
+        self.current_line = 0;
+    }
+
+    pub fn get_selected(&self) -> Option<&FuzzyCandidate> {
+        self.results
+            .get(self.current_line)
+            .and_then(|result| self.candidates.get(result.candidate))
+    }
+
+    pub fn move_cursor_down(&mut self) {
+        if self.current_line < self.results.len().saturating_sub(1) {
+            self.current_line += 1;
+        }
+    }
+
+    pub fn move_cursor_up(&mut self) {
+        if self.current_line > 0 {
+            self.current_line -= 1;
+        }
+    }
+}
+
+fn is_boundary(prev: Option<char>) -> bool {
+    match prev {
+        None => true,
+        Some(c) => !c.is_alphanumeric(),
+    }
+}
+
+// Scores every alignment of the pattern in the text and keeps the best one:
+// matches after separators and runs of consecutive matches are rewarded,
+// gaps between matched characters are penalised. Case insensitive.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let pattern: Vec<char> = pattern
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    if pattern.is_empty() {
+        return Some((0, vec![]));
+    }
+
+    let text_chars: Vec<char> = text.chars().collect();
+    let lowered: Vec<char> = text_chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let (m, n) = (pattern.len(), lowered.len());
+
+    if m > n {
+        return None;
+    }
+
+    // scores[i][j]: best score with pattern[i] matched at text[j]
+    let mut scores = vec![vec![None::<i64>; n]; m];
+    let mut parents = vec![vec![0usize; n]; m];
+
+    for i in 0..m {
+        // best of scores[i - 1][k] + k * gap for k < j, tracked while walking j
+        let mut best_prev: Option<(i64, usize)> = None;
+
+        for j in 0..n {
+            if i > 0 && j > 0 {
+                if let Some(prev) = scores[i - 1][j - 1] {
+                    let candidate = prev + (j as i64 - 1) * PENALTY_GAP;
+                    if best_prev.is_none_or(|(best, _)| candidate > best) {
+                        best_prev = Some((candidate, j - 1));
+                    }
+                }
+            }
+
+            if lowered[j] != pattern[i] {
+                continue;
+            }
+
+            let prev_char = if j > 0 { Some(text_chars[j - 1]) } else { None };
+            let mut base = SCORE_MATCH;
+            if is_boundary(prev_char) {
+                base += BONUS_BOUNDARY;
+            }
+            if j == 0 {
+                base += BONUS_FIRST_CHAR;
+            }
+
+            if i == 0 {
+                scores[i][j] = Some(base - j as i64 * PENALTY_GAP);
+                continue;
+            }
+
+            let consecutive = if j > 0 {
+                scores[i - 1][j - 1].map(|prev| prev + BONUS_CONSECUTIVE)
+            } else {
+                None
+            };
+            let gapped = best_prev.map(|(best, k)| (best - (j as i64 - 1) * PENALTY_GAP, k));
+
+            let chosen = match (consecutive, gapped) {
+                (Some(c), Some((g, k))) if g > c => Some((g, k)),
+                (Some(c), _) => Some((c, j - 1)),
+                (None, gapped) => gapped,
+            };
+
+            if let Some((score, k)) = chosen {
+                scores[i][j] = Some(score + base);
+                parents[i][j] = k;
+            }
+        }
+    }
+
+    let (mut j, score) = scores[m - 1]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, score)| score.map(|s| (j, s)))
+        .max_by_key(|(_, score)| *score)?;
+
+    let mut positions = vec![j; m];
+    for i in (1..m).rev() {
+        j = parents[i][j];
+        positions[i - 1] = j;
+    }
+
+    Some((score, positions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(pattern: &str, text: &str) -> i64 {
+        fuzzy_match(pattern, text).unwrap().0
+    }
+
+    #[test]
+    fn returns_the_matched_positions() {
+        let (_, positions) = fuzzy_match("ngx", "nginx.service").unwrap();
+        assert_eq!(positions, vec![0, 1, 4]);
+
+        // the s after the separator beats the earlier one
+        let (_, positions) = fuzzy_match("s", "bus-sock").unwrap();
+        assert_eq!(positions, vec![4]);
+    }
+
+    #[test]
+    fn boundaries_and_runs_rank_higher() {
+        assert!(score("ds", "dbus-sock") > score("ds", "dbusxsock"));
+        assert!(score("abc", "abcxx") > score("abc", "axbxc"));
+        assert!(score("ssh", "ssh.service") > score("ssh", "sessions-shell"));
+    }
+
+    #[test]
+    fn ignores_case() {
+        assert_eq!(fuzzy_match("NGX", "nginx"), fuzzy_match("ngx", "NGINX"));
+        assert_eq!(fuzzy_match("ngx", "NGINX").unwrap().1, vec![0, 1, 4]);
+    }
+
+    #[test]
+    fn no_match_is_none() {
+        assert!(fuzzy_match("xyz", "nginx.service").is_none());
+        assert!(fuzzy_match("nginxx", "nginx").is_none());
+        assert_eq!(fuzzy_match(" ", "nginx"), Some((0, vec![])));
+    }
+
+    #[test]
+    fn positions_count_chars_not_bytes() {
+        let (_, positions) = fuzzy_match("ét", "café-thé").unwrap();
+        assert_eq!(positions, vec![3, 5]);
+        assert_eq!(fuzzy_match("CAFÉ", "café").unwrap().1, vec![0, 1, 2, 3]);
+    }
+}
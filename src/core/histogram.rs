@@ -0,0 +1,131 @@
+use crate::core::journal::JournalLog;
+
+pub const HISTOGRAM_BUCKETS: usize = 60;
+
+#[derive(Debug, Clone, Default)]
+pub struct Bucket {
+    // entries per syslog priority, emerg (0) first
+    pub counts: [usize; 8],
+}
+
+impl Bucket {
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    // the most severe priority with entries in this bucket
+    pub fn top_priority(&self) -> Option<u8> {
+        self.counts
+            .iter()
+            .position(|count| *count > 0)
+            .map(|idx| idx as u8)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Histogram {
+    pub start: u64,
+    pub width: u64,
+    pub buckets: Vec<Bucket>,
+}
+
+impl Histogram {
+    // the time between the oldest and newest entry split into equal buckets
+    pub fn new(entries: &[JournalLog]) -> Option<Self> {
+        let start = entries.iter().map(|log| log.realtime).min()?;
+        let end = entries.iter().map(|log| log.realtime).max()?;
+        let width = ((end - start) / HISTOGRAM_BUCKETS as u64 + 1).max(1);
+
+        let mut histogram = Self {
+            start,
+            width,
+            buckets: vec![Bucket::default(); HISTOGRAM_BUCKETS],
+        };
+
+        for log in entries {
+            let idx = histogram.get_bucket_index(log.realtime);
+            histogram.buckets[idx].counts[log.priority.min(7) as usize] += 1;
+        }
+
+        Some(histogram)
+    }
+
+    pub fn get_bucket_index(&self, realtime: u64) -> usize {
+        (((realtime.saturating_sub(self.start)) / self.width) as usize).min(HISTOGRAM_BUCKETS - 1)
+    }
+
+    pub fn get_bucket_start(&self, idx: usize) -> u64 {
+        self.start + idx as u64 * self.width
+    }
+
+    // the first entry in list order inside the bucket
+    pub fn find_entry(&self, entries: &[JournalLog], idx: usize) -> Option<usize> {
+        entries
+            .iter()
+            .position(|log| self.get_bucket_index(log.realtime) == idx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(realtime: u64, priority: u8) -> JournalLog {
+        JournalLog {
+            realtime,
+            priority,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn oldest_and_newest_are_the_outer_buckets() {
+        let entries = [entry(1_000, 6), entry(1_599, 6), entry(1_300, 6)];
+        let histogram = Histogram::new(&entries).unwrap();
+
+        assert_eq!(histogram.width, 10);
+        assert_eq!(histogram.get_bucket_index(1_000), 0);
+        assert_eq!(histogram.get_bucket_index(1_599), HISTOGRAM_BUCKETS - 1);
+        assert_eq!(histogram.get_bucket_start(HISTOGRAM_BUCKETS - 1), 1_590);
+        // times outside the range fall into the outer buckets
+        assert_eq!(histogram.get_bucket_index(0), 0);
+        assert_eq!(histogram.get_bucket_index(9_999), HISTOGRAM_BUCKETS - 1);
+        assert_eq!(histogram.find_entry(&entries, 30), Some(2));
+        assert_eq!(histogram.find_entry(&entries, 31), None);
+    }
+
+    #[test]
+    fn a_single_entry_fills_the_first_bucket() {
+        let histogram = Histogram::new(&[entry(5_000, 3)]).unwrap();
+
+        assert_eq!(histogram.width, 1);
+        assert_eq!(histogram.buckets.len(), HISTOGRAM_BUCKETS);
+        assert_eq!(histogram.buckets[0].total(), 1);
+        assert!(histogram.buckets[1..]
+            .iter()
+            .all(|bucket| bucket.total() == 0));
+        assert!(Histogram::new(&[]).is_none());
+    }
+
+    #[test]
+    fn counts_entries_per_priority() {
+        let entries = [
+            entry(0, 6),
+            entry(0, 3),
+            entry(0, 3),
+            entry(0, 0),
+            // out of range priorities count as debug
+            entry(0, 9),
+            entry(100, 4),
+        ];
+        let histogram = Histogram::new(&entries).unwrap();
+
+        assert_eq!(histogram.buckets[0].counts, [1, 0, 0, 2, 0, 0, 1, 1]);
+        assert_eq!(histogram.buckets[0].top_priority(), Some(0));
+        assert_eq!(
+            histogram.buckets[histogram.get_bucket_index(100)].top_priority(),
+            Some(4)
+        );
+        assert_eq!(histogram.buckets[1].top_priority(), None);
+    }
+}
@@ -1,32 +1,219 @@
 use crate::app::{App, Events};
-use crate::core::clipboard::yank_to_clipboard;
+use crate::core::command::{parse_command, Command};
+use crate::core::filter::{toggle_filter, UnitFilter, QUICK_FILTERS};
+use crate::core::keymap::{get_action_name, is_available, Action, KeyChord, KeyContext, KeyMatch};
+use crate::core::motion::Motion;
+use crate::core::mouse::{Click, WHEEL_LINES};
 use crate::ui::ui::View;
-use crossterm::event::{self, Event, KeyCode, KeyEvent};
-use log::info;
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use std::time::Duration;
 
+const EVENT_POLL_TIMEOUT: Duration = Duration::from_millis(100);
+
+// polling lets the ui redraw while logs are loaded in the background
 pub fn handle_key_events(app: &mut App) -> Option<Events> {
-    if let Event::Key(key) = event::read().expect("Error keyboard input") {
-        if app.ui.is_in_logs {
-            if app.ui.is_in_search_mode {
-                return handle_search_key_events(app, key);
-            }
-            return handle_logs_key_events(app, key);
-        }
+    if !event::poll(EVENT_POLL_TIMEOUT).unwrap_or(false) {
+        return None;
+    }
+
+    match event::read().expect("Error keyboard input") {
+        Event::Key(key) => handle_key_event(app, key),
+        Event::Mouse(mouse) => handle_mouse_event(app, mouse),
+        _ => None,
+    }
+}
+
+fn handle_key_event(app: &mut App, key: KeyEvent) -> Option<Events> {
+    if app.ui.is_in_command_mode {
+        return handle_command_key_events(app, key);
+    }
+    if app.ui.is_in_global_search_mode {
+        return handle_global_search_key_events(app, key);
+    }
+    if app.ui.is_showing_global_search {
+        return handle_global_search_results_key_events(app, key);
+    }
+    if app.ui.is_showing_fuzzy_finder {
+        return handle_fuzzy_finder_key_events(app, key);
+    }
+    if app.ui.is_showing_filters {
+        return handle_filters_key_events(app, key);
+    }
+    if app.ui.is_showing_match_builder {
+        return handle_match_builder_key_events(app, key);
+    }
+    if app.ui.is_showing_patterns {
+        return handle_patterns_key_events(app, key);
+    }
+    if app.ui.is_in_export_prompt {
+        return handle_export_prompt_key_events(app, key);
+    }
+    if app.ui.is_in_bundle_prompt {
+        return handle_bundle_prompt_key_events(app, key);
+    }
+    if app.ui.is_in_jump_prompt {
+        return handle_jump_prompt_key_events(app, key);
+    }
+    if app.ui.is_in_annotation_prompt {
+        return handle_annotation_prompt_key_events(app, key);
+    }
+    if app.ui.is_in_confirm_prompt {
+        return handle_confirm_prompt_key_events(app, key);
+    }
+    if app.ui.is_showing_bookmarks {
+        return handle_bookmarks_key_events(app, key);
+    }
+    if app.ui.is_in_logs {
         if app.ui.is_in_search_mode {
             return handle_search_key_events(app, key);
         }
-        if app.ui.is_showing_docs {
-            return handle_see_docs_key_events(key);
+        if app.ui.is_in_histogram {
+            return handle_histogram_key_events(app, key);
         }
-        return handle_services_key_events(app, key);
+        return handle_logs_key_events(app, key);
+    }
+    if app.ui.is_in_search_mode {
+        return handle_search_key_events(app, key);
+    }
+    if app.ui.is_showing_docs {
+        return handle_see_docs_key_events(app, key);
+    }
+    if app.ui.view == View::Dashboard {
+        return handle_dashboard_key_events(app, key);
+    }
+    handle_services_key_events(app, key)
+}
+
+// Lists in modals scroll as if the arrow keys were pressed, prompts and
+// the other modals keep the mouse away from the panes behind them.
+fn handle_mouse_event(app: &mut App, mouse: MouseEvent) -> Option<Events> {
+    let is_down = match mouse.kind {
+        MouseEventKind::ScrollDown => Some(true),
+        MouseEventKind::ScrollUp => Some(false),
+        _ => None,
+    };
+
+    let is_showing_list = app.ui.is_showing_global_search
+        || app.ui.is_showing_fuzzy_finder
+        || app.ui.is_showing_match_builder
+        || app.ui.is_showing_patterns
+        || app.ui.is_showing_bookmarks;
+    if is_showing_list && !app.ui.is_in_command_mode {
+        let code = if is_down? { KeyCode::Down } else { KeyCode::Up };
+        return handle_key_event(app, KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    let is_blocked = app.ui.is_in_command_mode
+        || app.ui.is_in_global_search_mode
+        || app.ui.is_showing_filters
+        || app.ui.is_in_export_prompt
+        || app.ui.is_in_bundle_prompt
+        || app.ui.is_in_jump_prompt
+        || app.ui.is_in_annotation_prompt
+        || app.ui.is_in_confirm_prompt
+        || app.ui.is_in_search_mode
+        || app.ui.is_in_histogram
+        || app.ui.is_showing_help
+        || app.ui.is_showing_line_in_modal
+        || app.ui.is_showing_docs;
+    if is_blocked {
+        return None;
+    }
+
+    let (column, row) = (mouse.column, mouse.row);
+    let targets = app.ui.mouse_targets.borrow().clone();
+
+    // the pane under the mouse gets the focus in split layouts
+    let is_over_logs = targets
+        .logs
+        .as_ref()
+        .is_some_and(|logs| logs.contains(column, row));
+    let is_over_services = [&targets.services, &targets.dashboard]
+        .into_iter()
+        .flatten()
+        .any(|services| services.contains(column, row))
+        || targets.get_tab(column, row).is_some();
+    if is_over_services && app.ui.is_in_logs {
+        app.ui.focus_services();
+    }
+    if is_over_logs && !app.ui.is_in_logs {
+        app.ui.focus_logs();
+    }
+
+    if let Some(is_down) = is_down {
+        scroll_with_wheel(app, is_down);
+        return None;
+    }
+    if mouse.kind != MouseEventKind::Down(MouseButton::Left) {
+        return None;
+    }
+
+    if let Some(view) = targets.get_tab(column, row) {
+        return open_view(app, view);
+    }
+
+    let click = Click::new(column, row);
+    let is_double = click.is_double(app.ui.last_click);
+    app.ui.last_click = if is_double { None } else { Some(click) };
+
+    if app.ui.is_in_logs {
+        let line = targets.logs?.get_line(column, row)?;
+        app.ui.set_current_line(line);
+        return is_double.then_some(Events::GetLineInModal);
+    }
+    if app.ui.view == View::Dashboard {
+        let line = targets.dashboard?.get_line(column, row)?;
+        app.dashboard.as_mut()?.current_line = line;
+        return if is_double {
+            app.open_dashboard_link()
+        } else {
+            None
+        };
+    }
+    let line = targets.services?.get_line(column, row)?;
+    app.ui.set_current_line(line);
+    if is_double {
+        return app.open_highlighted_service();
     }
     None
 }
 
-fn handle_see_docs_key_events(key: KeyEvent) -> Option<Events> {
-    match key.code {
-        KeyCode::Esc | KeyCode::Char('q') => Some(Events::Quit),
-        KeyCode::Char('E') => Some(Events::Docs),
+fn scroll_with_wheel(app: &mut App, is_down: bool) {
+    if app.ui.is_in_logs || app.ui.view != View::Dashboard {
+        let motion = if is_down { Motion::Down } else { Motion::Up };
+        app.move_cursor(motion, Some(WHEEL_LINES));
+        return;
+    }
+    if let Some(dashboard) = app.dashboard.as_mut() {
+        for _ in 0..WHEEL_LINES {
+            if is_down {
+                dashboard.move_cursor_down();
+            } else {
+                dashboard.move_cursor_up();
+            }
+        }
+    }
+}
+
+// the tabs of the header, like moving left and right between them
+fn open_view(app: &mut App, view: View) -> Option<Events> {
+    if view == app.ui.view {
+        return None;
+    }
+    if view == View::Dashboard {
+        return app.open_dashboard();
+    }
+    app.ui.set_current_line(0);
+    app.ui.set_view(view);
+    None
+}
+
+fn handle_see_docs_key_events(app: &App, key: KeyEvent) -> Option<Events> {
+    match app.config.keymap.get_action(KeyContext::Services, &key) {
+        Some(Action::Quit) => Some(Events::Quit),
+        Some(Action::Docs) => Some(Events::Docs),
         _ => None,
     }
 }
@@ -56,167 +243,808 @@ fn handle_search_key_events(app: &mut App, key: KeyEvent) -> Option<Events> {
     }
 }
 
-fn handle_logs_key_events(app: &mut App, key: KeyEvent) -> Option<Events> {
-    let logs_len = if let Some(logs_arc) = &app.logs {
-        let logs_map = logs_arc.lock().unwrap();
-        logs_map
-            .get(
-                &app.ui
-                    .selected_priority
-                    .unwrap_or(app.config.options.initial_priority),
-            )
-            .map(|logs| logs.len())
-            .unwrap_or(0)
+fn handle_global_search_key_events(app: &mut App, key: KeyEvent) -> Option<Events> {
+    let input = if app.ui.is_editing_search_since {
+        &mut app.ui.global_search_since
     } else {
-        0
+        &mut app.ui.global_search_query
     };
 
-    let allow_actions = !app.ui.is_showing_line_in_modal
-        && !app.ui.is_showing_help
-        && !app.ui.is_in_search_mode
-        && !app.ui.is_showing_docs;
+    match key.code {
+        KeyCode::Esc => {
+            app.ui.set_is_in_global_search_mode(false);
+            None
+        }
+        KeyCode::Tab => {
+            app.ui.is_editing_search_since = !app.ui.is_editing_search_since;
+            None
+        }
+        KeyCode::Backspace => {
+            input.pop();
+            None
+        }
+        KeyCode::Char(any) => {
+            input.push(any);
+            None
+        }
+        KeyCode::Enter => {
+            if app.ui.global_search_query.trim().is_empty() {
+                return None;
+            }
+            Some(Events::RunGlobalSearch)
+        }
+        _ => None,
+    }
+}
+
+// Popups move, open and close with the keys of the views and the key that
+// opened them. Esc, Enter and the arrows always work, the wheel sends them.
+fn get_popup_action(app: &App, key: &KeyEvent, toggle: Action) -> Option<Action> {
+    let action = match key.code {
+        KeyCode::Esc => Action::Quit,
+        KeyCode::Enter => Action::Open,
+        KeyCode::Down => Action::MoveDown,
+        KeyCode::Up => Action::MoveUp,
+        KeyCode::Left => Action::MoveLeft,
+        KeyCode::Right => Action::MoveRight,
+        _ => {
+            let keymap = &app.config.keymap;
+            if keymap.get_action(get_key_context(app), key) == Some(toggle) {
+                return Some(toggle);
+            }
+            return keymap.get_action(KeyContext::Popup, key);
+        }
+    };
+    Some(action)
+}
+
+fn handle_global_search_results_key_events(app: &mut App, key: KeyEvent) -> Option<Events> {
+    match get_popup_action(app, &key, Action::GlobalSearch)? {
+        Action::Quit => {
+            app.ui.set_is_showing_global_search(false);
+            None
+        }
+        Action::GlobalSearch => Some(Events::GlobalSearch),
+        Action::MoveDown => {
+            if let Some(search) = app.global_search.as_mut() {
+                search.move_cursor_down();
+            }
+            None
+        }
+        Action::MoveUp => {
+            if let Some(search) = app.global_search.as_mut() {
+                search.move_cursor_up();
+            }
+            None
+        }
+        Action::Open => app.open_global_search_result(),
+        _ => None,
+    }
+}
+
+fn handle_fuzzy_finder_key_events(app: &mut App, key: KeyEvent) -> Option<Events> {
+    let finder = app.fuzzy_finder.as_mut()?;
 
     match key.code {
-        KeyCode::Esc | KeyCode::Char('q') => Some(Events::Quit),
-        KeyCode::Char('?') => Some(Events::GetHelp),
-        KeyCode::Char('K') => Some(Events::GetLineInModal),
-        KeyCode::Char('/') => Some(Events::Search),
-        KeyCode::Char('y') => {
-            yank_to_clipboard(
-                app.ui
-                    .get_log_message(app)
-                    .unwrap_or("Error yanking log message".to_string()),
-            )
-            .ok();
+        KeyCode::Esc => {
+            app.close_fuzzy_finder();
+            None
+        }
+        KeyCode::Down => {
+            finder.move_cursor_down();
+            None
+        }
+        KeyCode::Up => {
+            finder.move_cursor_up();
             None
         }
+        KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            finder.move_cursor_down();
+            None
+        }
+        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            finder.move_cursor_up();
+            None
+        }
+        KeyCode::Backspace => {
+            finder.pop_char();
+            None
+        }
+        KeyCode::Char(any) => {
+            finder.push_char(any);
+            None
+        }
+        KeyCode::Enter => app.open_fuzzy_finder_result(),
+        _ => None,
+    }
+}
+
+fn handle_filters_key_events(app: &mut App, key: KeyEvent) -> Option<Events> {
+    if let Some(action) = get_popup_action(app, &key, Action::Filters) {
+        return match action {
+            Action::Quit | Action::Open | Action::Filters => Some(Events::Filters),
+            _ => None,
+        };
+    }
+
+    match key.code {
+        KeyCode::Char('c') => {
+            app.unit_filters.clear();
+            app.ui.set_current_line(0);
+            None
+        }
+        KeyCode::Char(any) => {
+            let filter = QUICK_FILTERS
+                .iter()
+                .find(|(hotkey, _)| *hotkey == any)
+                .and_then(|(_, filter)| UnitFilter::parse(filter))?;
+
+            toggle_filter(&mut app.unit_filters, filter);
+            app.ui.set_current_line(0);
+            None
+        }
+        _ => None,
+    }
+}
+
+fn handle_match_builder_key_events(app: &mut App, key: KeyEvent) -> Option<Events> {
+    let action = get_popup_action(app, &key, Action::MatchBuilder);
+    let builder = app.match_builder.as_mut()?;
+
+    match (action, key.code) {
+        (Some(Action::Quit | Action::MatchBuilder), _) => app.close_match_builder(),
+        (Some(Action::MoveDown), _) => {
+            builder.move_cursor_down();
+            None
+        }
+        (Some(Action::MoveUp), _) => {
+            builder.move_cursor_up();
+            None
+        }
+        (Some(Action::MoveRight), _) | (None, KeyCode::Tab) => {
+            builder.next_field();
+            Some(Events::GetFieldValues)
+        }
+        (Some(Action::MoveLeft), _) | (None, KeyCode::BackTab) => {
+            builder.prev_field();
+            Some(Events::GetFieldValues)
+        }
+        (Some(Action::Open), _) | (None, KeyCode::Char(' ')) => {
+            app.toggle_selected_match();
+            None
+        }
+        (None, KeyCode::Char('c')) => {
+            app.clear_matches();
+            None
+        }
+        _ => None,
+    }
+}
+
+fn handle_patterns_key_events(app: &mut App, key: KeyEvent) -> Option<Events> {
+    // Esc steps back out of a pattern like moving left
+    let action = match key.code {
+        KeyCode::Esc => Some(Action::MoveLeft),
+        _ => get_popup_action(app, &key, Action::Patterns),
+    };
+    let view = app.patterns.as_mut()?;
+
+    match action? {
+        Action::Quit | Action::Patterns => app.close_patterns(),
+        Action::MoveLeft => {
+            if view.drill.is_some() {
+                view.drill_out();
+            } else {
+                app.close_patterns();
+            }
+        }
+        Action::MoveDown => view.move_cursor_down(),
+        Action::MoveUp => view.move_cursor_up(),
+        Action::Open | Action::MoveRight => {
+            if view.drill.is_some() {
+                app.open_pattern_entry();
+            } else {
+                view.drill_in();
+            }
+        }
+        _ => {}
+    }
+    None
+}
+
+fn handle_export_prompt_key_events(app: &mut App, key: KeyEvent) -> Option<Events> {
+    match key.code {
+        KeyCode::Esc => {
+            app.ui.set_is_in_export_prompt(false);
+            None
+        }
+        KeyCode::Enter => {
+            app.ui.set_is_in_export_prompt(false);
+            Some(Events::Export)
+        }
+        KeyCode::Tab => {
+            app.cycle_export_format();
+            None
+        }
+        KeyCode::Backspace => {
+            app.ui.export_path.pop();
+            None
+        }
+        KeyCode::Char(any) => {
+            app.ui.export_path.push(any);
+            None
+        }
+        _ => None,
+    }
+}
+
+fn handle_bundle_prompt_key_events(app: &mut App, key: KeyEvent) -> Option<Events> {
+    match key.code {
+        KeyCode::Esc => {
+            app.ui.set_is_in_bundle_prompt(false);
+            app.bundle_unit = None;
+            None
+        }
+        KeyCode::Enter => {
+            app.ui.set_is_in_bundle_prompt(false);
+            Some(Events::Bundle)
+        }
+        KeyCode::Backspace => {
+            app.ui.bundle_since.pop();
+            None
+        }
+        KeyCode::Char(any) => {
+            app.ui.bundle_since.push(any);
+            None
+        }
+        _ => None,
+    }
+}
+
+fn handle_jump_prompt_key_events(app: &mut App, key: KeyEvent) -> Option<Events> {
+    match key.code {
+        KeyCode::Esc => {
+            app.ui.set_is_in_jump_prompt(false);
+            None
+        }
+        KeyCode::Enter => {
+            let input = app.ui.jump_time.clone();
+            app.ui.set_is_in_jump_prompt(false);
+            app.jump_to_time(&input)
+        }
+        KeyCode::Backspace => {
+            app.ui.jump_time.pop();
+            None
+        }
+        KeyCode::Char(any) => {
+            app.ui.jump_time.push(any);
+            None
+        }
+        _ => None,
+    }
+}
+
+// only y goes ahead, any other key drops the action
+fn handle_confirm_prompt_key_events(app: &mut App, key: KeyEvent) -> Option<Events> {
+    app.ui.set_is_in_confirm_prompt(false);
+    let event = app.confirm_event.take();
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Char('Y') => event,
         _ => {
-            if allow_actions {
-                match key.code {
-                    KeyCode::Down | KeyCode::Char('j') => {
-                        app.ui.move_cursor_down(logs_len);
-                        None
-                    }
-                    KeyCode::Up | KeyCode::Char('k') => {
-                        app.ui.move_cursor_up();
-                        None
-                    }
-                    KeyCode::Left | KeyCode::Char('h') => {
-                        if let Some(p) = app.ui.selected_priority {
-                            if p > 1 {
-                                app.ui.set_priority(p - 1);
-                            }
-                        }
-                        None
-                    }
-                    KeyCode::Right | KeyCode::Char('l') => {
-                        if let Some(p) = app.ui.selected_priority {
-                            if p < 7 {
-                                app.ui.set_priority(p + 1);
-                            }
-                        }
-                        None
-                    }
-                    KeyCode::Char('c') => {
-                        app.clear_logs();
-                        app.ui.is_in_logs = false;
-                        app.ui.set_priority(app.config.options.initial_priority);
-                        None
-                    }
-                    KeyCode::Char(key) if ('1'..='7').contains(&key) => {
-                        app.ui.set_current_line(0);
-                        app.ui.selected_priority = Some(key.to_digit(10).unwrap() as u8);
-                        None
-                    }
-                    _ => None,
-                }
+            app.pending_unit_action = None;
+            None
+        }
+    }
+}
+
+fn handle_annotation_prompt_key_events(app: &mut App, key: KeyEvent) -> Option<Events> {
+    match key.code {
+        KeyCode::Esc => {
+            app.ui.set_is_in_annotation_prompt(false);
+            app.annotating = None;
+            None
+        }
+        KeyCode::Enter => {
+            app.ui.set_is_in_annotation_prompt(false);
+            app.save_annotation()
+        }
+        KeyCode::Backspace => {
+            app.ui.annotation.pop();
+            None
+        }
+        KeyCode::Char(any) => {
+            app.ui.annotation.push(any);
+            None
+        }
+        _ => None,
+    }
+}
+
+fn handle_bookmarks_key_events(app: &mut App, key: KeyEvent) -> Option<Events> {
+    if let Some(action) = get_popup_action(app, &key, Action::Bookmarks) {
+        return match action {
+            Action::Quit | Action::Bookmarks => {
+                app.ui.set_is_showing_bookmarks(false);
+                None
+            }
+            Action::MoveDown => {
+                app.move_bookmarks_cursor(true);
+                None
+            }
+            Action::MoveUp => {
+                app.move_bookmarks_cursor(false);
+                None
+            }
+            Action::Open => app.open_bookmark(),
+            _ => None,
+        };
+    }
+
+    match key.code {
+        KeyCode::Char('a') => {
+            app.open_annotation_prompt();
+            None
+        }
+        KeyCode::Char('d') => app.delete_bookmark(),
+        _ => None,
+    }
+}
+
+fn handle_histogram_key_events(app: &mut App, key: KeyEvent) -> Option<Events> {
+    match get_popup_action(app, &key, Action::Histogram)? {
+        Action::Quit | Action::Histogram => {
+            app.toggle_histogram_focus();
+            None
+        }
+        Action::MoveRight => {
+            app.move_histogram_bucket(true);
+            None
+        }
+        Action::MoveLeft => {
+            app.move_histogram_bucket(false);
+            None
+        }
+        Action::Open => {
+            app.jump_to_histogram_bucket();
+            None
+        }
+        _ => None,
+    }
+}
+
+fn handle_command_key_events(app: &mut App, key: KeyEvent) -> Option<Events> {
+    match key.code {
+        KeyCode::Esc => {
+            app.ui.set_is_in_command_mode(false);
+            None
+        }
+        KeyCode::Enter => {
+            let line = app.ui.command_line.clone();
+            app.ui.set_is_in_command_mode(false);
+            run_command_line(app, &line)
+        }
+        KeyCode::Tab => {
+            app.complete_command(true);
+            None
+        }
+        KeyCode::BackTab => {
+            app.complete_command(false);
+            None
+        }
+        KeyCode::Backspace if app.ui.command_line.is_empty() => {
+            app.ui.set_is_in_command_mode(false);
+            None
+        }
+        KeyCode::Backspace => {
+            app.ui.command_line.pop();
+            app.ui.command_completion = None;
+            None
+        }
+        KeyCode::Char(any) => {
+            app.ui.command_line.push(any);
+            app.ui.command_completion = None;
+            None
+        }
+        _ => None,
+    }
+}
+
+fn run_command_line(app: &mut App, line: &str) -> Option<Events> {
+    if line.trim().is_empty() {
+        return None;
+    }
+
+    match parse_command(line) {
+        Ok(Command::Action(action)) => {
+            let context = get_key_context(app);
+            if is_available(action, context) {
+                run_action(app, context, action, None, true)
             } else {
+                app.ui.notify(
+                    format!("{} is not available here", get_action_name(action)),
+                    true,
+                );
                 None
             }
         }
+        Ok(command) => app.run_command(command),
+        Err(e) => {
+            app.ui.notify(e, true);
+            None
+        }
     }
 }
 
-fn handle_services_key_events(app: &mut App, key: crossterm::event::KeyEvent) -> Option<Events> {
-    let services_len = match &app.services {
-        Some((u, f)) => {
-            if app.ui.view == View::ServiceUnits {
-                u.len()
+fn get_key_context(app: &App) -> KeyContext {
+    if app.ui.is_in_logs {
+        KeyContext::Logs
+    } else if app.ui.view == View::Dashboard {
+        KeyContext::Dashboard
+    } else {
+        KeyContext::Services
+    }
+}
+
+// The command line runs actions as if their key was pressed. Motions are
+// shared by the services and the logs, a count repeats them.
+fn run_action(
+    app: &mut App,
+    context: KeyContext,
+    action: Action,
+    count: Option<usize>,
+    allow_actions: bool,
+) -> Option<Events> {
+    if allow_actions && context != KeyContext::Dashboard {
+        if let Some(motion) = Motion::from_action(action) {
+            app.move_cursor(motion, count);
+            return None;
+        }
+        if matches!(action, Action::SetMark | Action::JumpToMark) {
+            app.ui.pending_keys.set_mark(action);
+            return None;
+        }
+    }
+
+    match context {
+        KeyContext::Logs => handle_logs_action(app, action, allow_actions),
+        KeyContext::Dashboard => handle_dashboard_action(app, action, allow_actions),
+        KeyContext::Services => handle_services_action(app, action, allow_actions),
+        KeyContext::Popup => None,
+    }
+}
+
+// Counts, sequences like gg and the letter of a mark are collected in the
+// pending keys until they make up an action.
+fn handle_view_key_events(
+    app: &mut App,
+    context: KeyContext,
+    key: KeyEvent,
+    allow_actions: bool,
+) -> Option<Events> {
+    let chord = KeyChord::from_event(&key);
+    let mut pending = std::mem::take(&mut app.ui.pending_keys);
+
+    if let Some(mark) = pending.mark {
+        return match key.code {
+            KeyCode::Char(letter) if letter.is_ascii_lowercase() => match mark {
+                Action::SetMark => app.set_mark(letter),
+                _ => {
+                    app.jump_to_mark(letter);
+                    None
+                }
+            },
+            _ => None,
+        };
+    }
+
+    if key.code == KeyCode::Esc && !pending.is_empty() {
+        return None;
+    }
+
+    // Esc leaves the visual selection whatever quit is bound to
+    if context == KeyContext::Logs && key.code == KeyCode::Esc && app.ui.visual_anchor.is_some() {
+        app.ui.toggle_visual_mode();
+        return None;
+    }
+
+    let keymap = &app.config.keymap;
+    if let KeyCode::Char(digit @ '0'..='9') = key.code {
+        let is_count = allow_actions
+            && pending.chords.is_empty()
+            && (digit != '0' || pending.count.is_some())
+            && keymap.match_keys(context, &[chord]) == KeyMatch::None;
+        if is_count {
+            // a second digit makes it a count, not a priority
+            match pending.switched_from.take() {
+                Some(shown) => app.restore_priority(shown),
+                None if pending.count.is_none() => {
+                    let digit = digit.to_digit(10).unwrap_or_default() as usize;
+                    pending.switched_from = app.switch_priority_from_digit(digit);
+                }
+                None => {}
+            }
+            pending.push_digit(digit.to_digit(10).unwrap_or_default());
+            app.ui.pending_keys = pending;
+            return None;
+        }
+    }
+
+    pending.push_chord(chord);
+    match keymap.match_keys(context, &pending.chords) {
+        KeyMatch::Prefix => {
+            app.ui.pending_keys = pending;
+            None
+        }
+        KeyMatch::Action(action) => {
+            let Some(shown) = pending.switched_from else {
+                return run_action(app, context, action, pending.count, allow_actions);
+            };
+            // the digit already switched the priority unless a motion takes it
+            if allow_actions && takes_count(action) {
+                app.restore_priority(shown);
+                run_action(app, context, action, pending.count, allow_actions)
             } else {
-                f.len()
+                run_action(app, context, action, None, allow_actions)
             }
         }
-        None => 0,
-    };
+        KeyMatch::None => None,
+    }
+}
+
+fn takes_count(action: Action) -> bool {
+    Motion::from_action(action).is_some() || matches!(action, Action::SetMark | Action::JumpToMark)
+}
 
+fn handle_logs_key_events(app: &mut App, key: KeyEvent) -> Option<Events> {
     let allow_actions = !app.ui.is_showing_line_in_modal
         && !app.ui.is_showing_help
         && !app.ui.is_in_search_mode
         && !app.ui.is_showing_docs;
 
-    match key.code {
-        KeyCode::Esc | KeyCode::Char('q') => Some(Events::Quit),
-        KeyCode::Char('?') => Some(Events::GetHelp),
-        KeyCode::Char('/') => Some(Events::Search),
-        KeyCode::Char('K') => Some(Events::GetLineInModal),
-        KeyCode::Char('E') => Some(Events::Docs),
-        _ => {
-            if allow_actions {
-                match key.code {
-                    KeyCode::Down | KeyCode::Char('j') => {
-                        app.ui.move_cursor_down(services_len);
-                        None
-                    }
-                    KeyCode::Up | KeyCode::Char('k') => {
-                        app.ui.move_cursor_up();
-                        None
-                    }
-                    KeyCode::Right | KeyCode::Char('l') => {
-                        if app.ui.view == View::ServiceUnits {
-                            app.ui.set_current_line(0);
-                            app.ui.set_view(View::ServiceUnitFiles);
-                        }
-                        None
-                    }
-                    KeyCode::Left | KeyCode::Char('h') => {
-                        if app.ui.view == View::ServiceUnitFiles {
-                            app.ui.set_current_line(0);
-                            app.ui.set_view(View::ServiceUnits);
-                        }
-                        None
-                    }
-                    KeyCode::Enter => {
-                        if let Some((u, f)) = &app.services {
-                            match app.ui.view {
-                                View::ServiceUnits => {
-                                    info!("HIT ENTER FOR UNITS");
-                                    if let Some(service) = u.get(app.ui.current_line) {
-                                        info!("SELECTED SERVICE NOW {:?}", service);
-                                        app.selected_service = Some(service.name.clone());
-                                        app.ui.is_in_logs = true;
-                                        app.ui.set_current_line(0);
-                                    }
-                                }
-                                View::ServiceUnitFiles => {
-                                    info!("HIT ENTER FOR UNITSFILES");
-                                    if let Some(service) = f.get(app.ui.current_line) {
-                                        info!("SELECTED SERVICE NOW {:?}", service);
-                                        app.selected_service = Some(service.name.clone());
-                                        app.ui.is_in_logs = true;
-                                        app.ui.set_current_line(0);
-                                    }
-                                }
-                            }
-                            Some(Events::GetLogs)
-                        } else {
-                            None
-                        }
-                    }
-                    _ => None,
+    handle_view_key_events(app, KeyContext::Logs, key, allow_actions)
+}
+
+fn handle_logs_action(app: &mut App, action: Action, allow_actions: bool) -> Option<Events> {
+    match action {
+        Action::Quit => Some(Events::Quit),
+        Action::Help => Some(Events::GetHelp),
+        Action::ShowLine => Some(Events::GetLineInModal),
+        Action::Search => Some(Events::Search),
+        Action::Command => Some(Events::Command),
+        Action::GlobalSearch => Some(Events::GlobalSearch),
+        Action::MatchBuilder => app.open_match_builder(),
+        Action::Yank => Some(Events::Yank),
+        Action::Bookmarks => {
+            app.ui.set_is_showing_bookmarks(true);
+            None
+        }
+        _ if !allow_actions => None,
+        Action::Bookmark => app.toggle_bookmark(),
+        Action::Annotate => {
+            app.open_annotation_prompt();
+            None
+        }
+        Action::NextInvocation => {
+            app.jump_to_invocation(true);
+            None
+        }
+        Action::PrevInvocation => {
+            app.jump_to_invocation(false);
+            None
+        }
+        Action::FoldInvocation => {
+            app.toggle_fold_invocation();
+            None
+        }
+        Action::FoldOldInvocations => {
+            app.toggle_fold_old_invocations();
+            None
+        }
+        Action::Histogram => {
+            app.toggle_histogram_focus();
+            None
+        }
+        Action::Patterns => {
+            app.open_patterns();
+            None
+        }
+        Action::JumpToTime => {
+            app.ui.set_is_in_jump_prompt(true);
+            None
+        }
+        Action::Bundle => {
+            app.open_bundle_prompt();
+            None
+        }
+        Action::VisualSelection => {
+            app.ui.toggle_visual_mode();
+            None
+        }
+        Action::Export => {
+            app.open_export_prompt();
+            None
+        }
+        Action::CollapseDuplicates => {
+            app.cycle_dedupe_mode();
+            None
+        }
+        Action::ExpandDuplicates => {
+            app.toggle_expand_duplicates();
+            None
+        }
+        Action::ToggleInvocations => {
+            app.ui.is_grouping_invocations = !app.ui.is_grouping_invocations;
+            app.folded_invocations.clear();
+            None
+        }
+        Action::MoveLeft => {
+            if let Some(p) = app.ui.selected_priority {
+                if p > 1 {
+                    app.ui.set_priority(p - 1);
                 }
-            } else {
-                None
             }
+            None
+        }
+        Action::MoveRight => {
+            if let Some(p) = app.ui.selected_priority {
+                if p < 7 {
+                    app.ui.set_priority(p + 1);
+                }
+            }
+            None
+        }
+        Action::ScrollRight => {
+            app.ui.scroll_right();
+            None
         }
+        Action::ScrollLeft => {
+            app.ui.scroll_left();
+            None
+        }
+        Action::CloseLogs => {
+            app.close_logs();
+            None
+        }
+        Action::CloseTab => {
+            app.close_active_tab();
+            None
+        }
+        Action::NextTab => {
+            app.switch_tab(true);
+            None
+        }
+        Action::PrevTab => {
+            app.switch_tab(false);
+            None
+        }
+        Action::SwitchPane if app.is_split_layout() => {
+            app.ui.focus_services();
+            None
+        }
+        _ => None,
+    }
+}
+
+fn handle_dashboard_key_events(app: &mut App, key: KeyEvent) -> Option<Events> {
+    let allow_actions = !app.ui.is_showing_line_in_modal && !app.ui.is_showing_help;
+
+    let action = app.config.keymap.get_action(KeyContext::Dashboard, &key)?;
+    handle_dashboard_action(app, action, allow_actions)
+}
+
+fn handle_dashboard_action(app: &mut App, action: Action, allow_actions: bool) -> Option<Events> {
+    match action {
+        Action::Quit => Some(Events::Quit),
+        Action::Help => Some(Events::GetHelp),
+        Action::Command => Some(Events::Command),
+        Action::GlobalSearch => Some(Events::GlobalSearch),
+        Action::FindService => Some(Events::FuzzyFinder),
+        Action::Docs => Some(Events::Docs),
+        _ if !allow_actions => None,
+        Action::MoveDown => {
+            if let Some(dashboard) = app.dashboard.as_mut() {
+                dashboard.move_cursor_down();
+            }
+            None
+        }
+        Action::MoveUp => {
+            if let Some(dashboard) = app.dashboard.as_mut() {
+                dashboard.move_cursor_up();
+            }
+            None
+        }
+        Action::MoveRight => {
+            app.ui.set_current_line(0);
+            app.ui.set_view(View::ServiceUnits);
+            None
+        }
+        Action::Dashboard | Action::RefreshDashboard => app.open_dashboard(),
+        Action::Open => app.open_dashboard_link(),
+        Action::SwitchPane if app.is_split_layout() => app.focus_logs_pane(),
+        _ => None,
+    }
+}
+
+fn handle_services_key_events(app: &mut App, key: KeyEvent) -> Option<Events> {
+    let allow_actions = !app.ui.is_showing_line_in_modal
+        && !app.ui.is_showing_help
+        && !app.ui.is_in_search_mode
+        && !app.ui.is_showing_docs;
+
+    handle_view_key_events(app, KeyContext::Services, key, allow_actions)
+}
+
+fn handle_services_action(app: &mut App, action: Action, allow_actions: bool) -> Option<Events> {
+    match action {
+        Action::Quit => Some(Events::Quit),
+        Action::Help => Some(Events::GetHelp),
+        Action::Search => Some(Events::Search),
+        Action::Command => Some(Events::Command),
+        Action::GlobalSearch => Some(Events::GlobalSearch),
+        Action::FindService => Some(Events::FuzzyFinder),
+        Action::Filters => Some(Events::Filters),
+        Action::ShowLine => Some(Events::GetLineInModal),
+        Action::Docs => Some(Events::Docs),
+        Action::Dashboard => app.open_dashboard(),
+        Action::Bookmarks => {
+            app.ui.set_is_showing_bookmarks(true);
+            None
+        }
+        _ if !allow_actions => None,
+        Action::ScrollRight => {
+            app.ui.scroll_right();
+            None
+        }
+        Action::ScrollLeft => {
+            app.ui.scroll_left();
+            None
+        }
+        Action::SortColumn => {
+            app.cycle_sort_column();
+            None
+        }
+        Action::SortDirection => {
+            app.toggle_sort_direction();
+            None
+        }
+        Action::MoveRight => {
+            if app.ui.view == View::ServiceUnits {
+                app.ui.set_current_line(0);
+                app.ui.set_view(View::ServiceUnitFiles);
+            }
+            None
+        }
+        Action::MoveLeft => {
+            if app.ui.view == View::ServiceUnitFiles {
+                app.ui.set_current_line(0);
+                app.ui.set_view(View::ServiceUnits);
+            }
+            None
+        }
+        Action::OpenInTab => {
+            app.open_highlighted_service_in_tab();
+            None
+        }
+        Action::MarkUnit => {
+            app.toggle_mark_highlighted();
+            app.ui.move_cursor_down(app.get_services_len());
+            None
+        }
+        Action::OpenMarked => app.open_marked_units(),
+        Action::Bundle => {
+            app.open_bundle_prompt();
+            None
+        }
+        Action::NextTab => {
+            app.switch_tab(true);
+            None
+        }
+        Action::PrevTab => {
+            app.switch_tab(false);
+            None
+        }
+        Action::Open => app.open_highlighted_service(),
+        Action::SwitchPane if app.is_split_layout() => app.focus_logs_pane(),
+        _ => None,
     }
 }
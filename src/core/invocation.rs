@@ -0,0 +1,213 @@
+use crate::core::journal::{JournalLog, JournalLogMap};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone)]
+pub struct InvocationInfo {
+    pub start: u64,
+    pub end: u64,
+    pub exit_status: Option<String>,
+}
+
+pub type InvocationIndex = HashMap<String, InvocationInfo>;
+
+// a run of consecutive entries of one invocation in the shown priority
+#[derive(Debug, Clone)]
+pub struct InvocationGroup {
+    pub id: String,
+    pub first_line: usize,
+    pub count: usize,
+}
+
+impl InvocationGroup {
+    pub fn contains(&self, line: usize) -> bool {
+        (self.first_line..self.first_line + self.count).contains(&line)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogRow {
+    // a folded group is only its separator, which stands for its first entry
+    Separator { group: usize, entry: Option<usize> },
+    Entry(usize),
+}
+
+impl LogRow {
+    pub fn get_entry(&self) -> Option<usize> {
+        match self {
+            Self::Separator { entry, .. } => *entry,
+            Self::Entry(idx) => Some(*idx),
+        }
+    }
+}
+
+// Start, end and exit status are taken from the debug bucket, which holds
+// every entry, so they do not change with the selected priority.
+pub fn index_invocations(logs: &JournalLogMap) -> InvocationIndex {
+    let mut index: InvocationIndex = HashMap::new();
+    let Some(entries) = logs.keys().max().and_then(|p| logs.get(p)) else {
+        return index;
+    };
+
+    for log in entries.iter().filter(|log| !log.invocation.is_empty()) {
+        let info = index
+            .entry(log.invocation.clone())
+            .or_insert(InvocationInfo {
+                start: log.realtime,
+                end: log.realtime,
+                exit_status: None,
+            });
+
+        info.start = info.start.min(log.realtime);
+        info.end = info.end.max(log.realtime);
+        if info.exit_status.is_none() && !log.exit_status.is_empty() {
+            info.exit_status = Some(log.exit_status.clone());
+        }
+    }
+
+    index
+}
+
+pub fn group_invocations(entries: &[JournalLog]) -> Vec<InvocationGroup> {
+    let mut groups: Vec<InvocationGroup> = vec![];
+
+    for (idx, log) in entries.iter().enumerate() {
+        match groups.last_mut() {
+            Some(group) if group.id == log.invocation => group.count += 1,
+            _ => groups.push(InvocationGroup {
+                id: log.invocation.clone(),
+                first_line: idx,
+                count: 1,
+            }),
+        }
+    }
+
+    groups
+}
+
+pub fn build_log_rows(groups: &[InvocationGroup], folded: &HashSet<String>) -> Vec<LogRow> {
+    let mut rows = vec![];
+
+    for (idx, group) in groups.iter().enumerate() {
+        if folded.contains(&group.id) {
+            rows.push(LogRow::Separator {
+                group: idx,
+                entry: Some(group.first_line),
+            });
+            continue;
+        }
+
+        rows.push(LogRow::Separator {
+            group: idx,
+            entry: None,
+        });
+        rows.extend((group.first_line..group.first_line + group.count).map(LogRow::Entry));
+    }
+
+    rows
+}
+
+// the log lines the cursor can rest on
+pub fn get_visible_lines(groups: &[InvocationGroup], folded: &HashSet<String>) -> Vec<usize> {
+    build_log_rows(groups, folded)
+        .iter()
+        .filter_map(LogRow::get_entry)
+        .collect()
+}
+
+pub fn format_duration(micros: u64) -> String {
+    let seconds = micros / 1_000_000;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(invocation: &str, realtime: u64, exit_status: &str) -> JournalLog {
+        JournalLog {
+            invocation: invocation.to_string(),
+            realtime,
+            exit_status: exit_status.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn summary(groups: &[InvocationGroup]) -> Vec<(&str, usize, usize)> {
+        groups
+            .iter()
+            .map(|group| (group.id.as_str(), group.first_line, group.count))
+            .collect()
+    }
+
+    fn invocation_summary(index: &InvocationIndex) -> Vec<(String, u64, u64, Option<String>)> {
+        let mut summary: Vec<_> = index
+            .iter()
+            .map(|(id, info)| (id.clone(), info.start, info.end, info.exit_status.clone()))
+            .collect();
+        summary.sort();
+        summary
+    }
+
+    #[test]
+    fn groups_runs_of_an_invocation() {
+        let entries = [
+            entry("a", 1, ""),
+            entry("a", 2, ""),
+            entry("b", 3, ""),
+            entry("a", 4, ""),
+            entry("", 5, ""),
+            entry("", 6, ""),
+        ];
+
+        // an invocation that comes back starts a new group, entries without
+        // an id are grouped together
+        assert_eq!(
+            summary(&group_invocations(&entries)),
+            vec![("a", 0, 2), ("b", 2, 1), ("a", 3, 1), ("", 4, 2)]
+        );
+        assert!(group_invocations(&[]).is_empty());
+    }
+
+    #[test]
+    fn indexes_from_the_debug_bucket() {
+        let logs = JournalLogMap::from([
+            (3, vec![entry("a", 20, "")]),
+            (
+                7,
+                vec![
+                    entry("a", 30, ""),
+                    entry("a", 10, ""),
+                    entry("b", 40, "1"),
+                    entry("b", 50, "0"),
+                    entry("", 60, "2"),
+                ],
+            ),
+        ]);
+        let index = invocation_summary(&index_invocations(&logs));
+
+        assert_eq!(
+            index,
+            vec![
+                ("a".to_string(), 10, 30, None),
+                ("b".to_string(), 40, 50, Some("1".to_string())),
+            ]
+        );
+        assert!(index_invocations(&JournalLogMap::new()).is_empty());
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(0), "0s");
+        assert_eq!(format_duration(59_999_999), "59s");
+        assert_eq!(format_duration(61_000_000), "1m 1s");
+        assert_eq!(format_duration(7_380_000_000), "2h 3m");
+    }
+}
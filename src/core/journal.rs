@@ -1,5 +1,12 @@
-use crate::core::error::{Result, RounalError};
+use crate::core::{
+    error::{Result, RounalError},
+    matches::JournalMatch,
+    system::{Active, Load, ServiceUnits, Sub},
+};
+use chrono::{Local, TimeZone};
 use log::{error, info};
+use regex::RegexBuilder;
+use serde_json::Value;
 use std::{
     collections::HashMap,
     sync::{Arc, Mutex},
@@ -7,20 +14,107 @@ use std::{
 };
 use tokio::{process::Command, sync::mpsc};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct JournalLog {
     pub priority: u8,
     pub timestamp: String,
     pub log_message: String,
     pub hostname: String,
     pub service: String,
+    pub unit: String,
+    pub cursor: String,
+    pub realtime: u64,
+    pub invocation: String,
+    pub exit_status: String,
 }
 
 pub type Priority = u8;
 pub type JournalLogMap = HashMap<Priority, Vec<JournalLog>>;
 pub type SharedJournalLogs = Arc<Mutex<JournalLogMap>>;
 
-pub async fn get_journal_logs(service: &str) -> Result<SharedJournalLogs> {
+#[derive(Debug, Clone)]
+pub struct Preview {
+    pub service: String,
+    pub logs: Vec<JournalLog>,
+}
+
+pub type SharedPreview = Arc<Mutex<Option<Preview>>>;
+
+// --since and --boot of the opened logs, set from the command line
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimeWindow {
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub boot: Option<String>,
+}
+
+impl TimeWindow {
+    fn get_args(&self) -> Vec<String> {
+        let mut args = vec![];
+        if let Some(since) = &self.since {
+            args.push(format!("--since={}", since));
+        }
+        if let Some(until) = &self.until {
+            args.push(format!("--until={}", until));
+        }
+        if let Some(boot) = &self.boot {
+            args.push(format!("--boot={}", boot));
+        }
+        args
+    }
+
+    pub fn label(&self) -> Option<String> {
+        let mut parts = vec![];
+        if let Some(since) = &self.since {
+            parts.push(format!("since {}", since));
+        }
+        if let Some(until) = &self.until {
+            parts.push(format!("until {}", until));
+        }
+        if let Some(boot) = &self.boot {
+            parts.push(format!("boot {}", boot));
+        }
+        (!parts.is_empty()).then(|| parts.join(" "))
+    }
+}
+
+const SEARCH_LIMIT: usize = 5000;
+// entries read when journalctl can not --grep itself
+const SEARCH_SCAN_LIMIT: usize = 100000;
+const ENTIRE_JOURNAL_LIMIT: usize = 20000;
+
+pub const KERNEL_LOG: &str = "kernel";
+pub const ENTIRE_JOURNAL: &str = "journal";
+
+// Listed above the service units and opened like them, get_logs turns
+// their names into -k or no unit match at all.
+pub fn get_journal_sources() -> Vec<ServiceUnits> {
+    [
+        (KERNEL_LOG, "Kernel messages (journalctl -k)"),
+        (ENTIRE_JOURNAL, "Entire journal, every unit and process"),
+    ]
+    .into_iter()
+    .map(|(name, description)| ServiceUnits {
+        name: name.to_string(),
+        load: Load::Loaded,
+        active: Active::Active,
+        sub: Sub::Running,
+        description: description.to_string(),
+    })
+    .collect()
+}
+
+pub fn is_journal_source(name: &str) -> bool {
+    name == KERNEL_LOG || name == ENTIRE_JOURNAL
+}
+
+// several units are fetched in one journalctl call, which interleaves
+// their entries by time, field matches narrow all of them down
+pub async fn get_journal_logs(
+    units: &[String],
+    matches: &[JournalMatch],
+    window: &TimeWindow,
+) -> Result<SharedJournalLogs> {
     let logs_for_service = Arc::new(Mutex::new(HashMap::new()));
     let (sender, mut receiver) = mpsc::channel(7);
     info!("get_journal_logs called");
@@ -28,7 +122,9 @@ pub async fn get_journal_logs(service: &str) -> Result<SharedJournalLogs> {
     for p in 1..=7 {
         info!("In loop with priority: {}", p);
         let thread_logs = logs_for_service.clone();
-        let thread_service = service.to_string();
+        let thread_units = units.to_vec();
+        let thread_matches = matches.to_vec();
+        let thread_window = window.clone();
         let thread_sender = sender.clone();
 
         tokio::spawn(async move {
@@ -41,17 +137,18 @@ pub async fn get_journal_logs(service: &str) -> Result<SharedJournalLogs> {
                 p, thread_id, thread_name
             );
 
-            let logs = get_logs(thread_service, p)
+            // a bad --since or --boot fails every priority, the first error is returned
+            let result = get_logs(&thread_units, &thread_matches, &thread_window, p, None)
                 .await
-                .expect("Error getting logs for: {service} with priority: {p}");
-
-            thread_logs
-                .lock()
-                .map_err(|e| RounalError::JournalCtlError(format!("{:?}", e)))
-                .ok()
-                .map(|mut logs_map| logs_map.insert(p, logs));
+                .map(|logs| {
+                    thread_logs
+                        .lock()
+                        .map_err(|e| RounalError::JournalCtlError(format!("{:?}", e)))
+                        .ok()
+                        .map(|mut logs_map| logs_map.insert(p, logs));
+                });
 
-            if let Err(e) = thread_sender.send(()).await {
+            if let Err(e) = thread_sender.send(result).await {
                 error!("Error in thread sender: {:?}", e);
             }
 
@@ -63,32 +160,86 @@ pub async fn get_journal_logs(service: &str) -> Result<SharedJournalLogs> {
     }
 
     for x in 1..=7 {
-        if receiver.recv().await.is_none() {
-            return Err(RounalError::UnexpectedError(format!(
-                "Error receiving logs for priority {}",
-                x
-            )));
+        match receiver.recv().await {
+            Some(Ok(())) => {}
+            Some(Err(e)) => return Err(e),
+            None => {
+                return Err(RounalError::UnexpectedError(format!(
+                    "Error receiving logs for priority {}",
+                    x
+                )))
+            }
         }
     }
 
     Ok(logs_for_service)
 }
 
-async fn get_logs(service: String, priority: u8) -> Result<Vec<JournalLog>> {
-    let out = Command::new("sudo")
-        .arg("journalctl")
-        .arg("-u")
-        .arg(&service)
+pub async fn get_recent_logs(service: &str, priority: u8, lines: usize) -> Result<Vec<JournalLog>> {
+    get_logs(
+        &[service.to_string()],
+        &[],
+        &TimeWindow::default(),
+        priority,
+        Some(lines),
+    )
+    .await
+}
+
+fn add_unit_args(command: &mut Command, units: &[String]) {
+    for unit in units {
+        match unit.as_str() {
+            KERNEL_LOG => {
+                command.arg("-k");
+            }
+            ENTIRE_JOURNAL => {}
+            _ => {
+                command.arg("-u").arg(unit);
+            }
+        }
+    }
+}
+
+async fn get_logs(
+    units: &[String],
+    matches: &[JournalMatch],
+    window: &TimeWindow,
+    priority: u8,
+    lines: Option<usize>,
+) -> Result<Vec<JournalLog>> {
+    let mut command = Command::new("sudo");
+    command.arg("journalctl");
+    add_unit_args(&mut command, units);
+
+    // matches on the same field are OR'ed by journalctl, different fields AND'ed
+    for journal_match in matches {
+        command.arg(journal_match.label());
+    }
+    command.args(window.get_args());
+
+    command
         .arg("-r")
         .arg("-p")
         .arg(priority.to_string())
-        .output()
-        .await?;
+        .arg("-o")
+        .arg("json");
+
+    // the whole journal can be far too big to keep in memory
+    let lines = match lines {
+        None if units.iter().any(|unit| unit == ENTIRE_JOURNAL) => Some(ENTIRE_JOURNAL_LIMIT),
+        lines => lines,
+    };
+
+    if let Some(lines) = lines {
+        command.arg("-n").arg(lines.to_string());
+    }
+
+    let out = command.output().await?;
 
     if !out.status.success() {
         return Err(RounalError::JournalCtlError(format!(
             "{}, {}",
-            service,
+            units.join(" "),
             String::from_utf8_lossy(&out.stderr)
         )));
     }
@@ -96,23 +247,290 @@ async fn get_logs(service: String, priority: u8) -> Result<Vec<JournalLog>> {
     let stdout = String::from_utf8_lossy(&out.stdout);
     let logs: Vec<JournalLog> = stdout
         .lines()
-        .skip(1)
         .filter_map(|line| parse_log(line, &priority))
         .collect();
 
     Ok(logs)
 }
 
+// units with the most error or worse entries since the given time
+pub async fn get_error_counts(since: &str) -> Result<Vec<(String, usize)>> {
+    let stdout = run_journalctl(&[
+        "-p".to_string(),
+        "3".to_string(),
+        format!("--since={}", since),
+        "-o".to_string(),
+        "json".to_string(),
+        "--output-fields=_SYSTEMD_UNIT".to_string(),
+    ])
+    .await?;
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for entry in stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+    {
+        if let Some(unit) = get_field(&entry, "_SYSTEMD_UNIT") {
+            *counts.entry(unit).or_default() += 1;
+        }
+    }
+
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    Ok(counts)
+}
+
+#[derive(Debug, Clone)]
+pub struct Restart {
+    pub unit: String,
+    pub count: usize,
+    pub realtime: u64,
+}
+
+// systemd logs "Scheduled restart job" with the restarted unit in UNIT
+pub async fn get_restarts(since: &str) -> Result<Vec<Restart>> {
+    let stdout = run_journalctl(&[
+        "_PID=1".to_string(),
+        format!("--since={}", since),
+        "-r".to_string(),
+        "-o".to_string(),
+        "json".to_string(),
+    ])
+    .await?;
+
+    let mut restarts: Vec<Restart> = vec![];
+    for entry in stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+    {
+        let is_restart = get_field(&entry, "MESSAGE")
+            .is_some_and(|message| message.starts_with("Scheduled restart job"));
+        let (Some(unit), Some(realtime)) = (
+            get_field(&entry, "UNIT"),
+            get_field(&entry, "__REALTIME_TIMESTAMP").and_then(|r| r.parse::<u64>().ok()),
+        ) else {
+            continue;
+        };
+
+        if !is_restart {
+            continue;
+        }
+
+        // newest first, so the first entry of a unit is its latest restart
+        match restarts.iter_mut().find(|restart| restart.unit == unit) {
+            Some(restart) => restart.count += 1,
+            None => restarts.push(Restart {
+                unit,
+                count: 1,
+                realtime,
+            }),
+        }
+    }
+
+    Ok(restarts)
+}
+
+pub async fn get_last_error(unit: &str) -> Result<Option<JournalLog>> {
+    let logs = get_logs(&[unit.to_string()], &[], &TimeWindow::default(), 3, Some(1)).await?;
+    Ok(logs.into_iter().next())
+}
+
+// "Archived and active journals take up 1.2G in the file system."
+pub async fn get_disk_usage() -> Result<String> {
+    let stdout = run_journalctl(&["--disk-usage".to_string()]).await?;
+    let usage = stdout
+        .split("take up ")
+        .nth(1)
+        .and_then(|rest| rest.split_whitespace().next())
+        .unwrap_or(stdout.trim());
+
+    Ok(usage.to_string())
+}
+
+async fn run_journalctl(args: &[String]) -> Result<String> {
+    let out = Command::new("sudo")
+        .arg("journalctl")
+        .args(args)
+        .output()
+        .await?;
+
+    if !out.status.success() {
+        return Err(RounalError::JournalCtlError(format!(
+            "{}, {}",
+            args.join(" "),
+            String::from_utf8_lossy(&out.stderr)
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&out.stdout).to_string())
+}
+
+// only the values found in the logs of the units
+pub async fn get_field_values(field: &str, units: &[String]) -> Result<Vec<String>> {
+    info!("get_field_values called: {} {:?}", field, units);
+
+    let mut command = Command::new("sudo");
+    command.arg("journalctl");
+    add_unit_args(&mut command, units);
+    let out = command.arg("-F").arg(field).output().await?;
+
+    if !out.status.success() {
+        return Err(RounalError::JournalCtlError(format!(
+            "-F {}, {}",
+            field,
+            String::from_utf8_lossy(&out.stderr)
+        )));
+    }
+
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let mut values: Vec<String> = stdout
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::to_string)
+        .collect();
+
+    // numeric fields like _PID read better in numeric order
+    values.sort_by(|a, b| match (a.parse::<u64>(), b.parse::<u64>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    });
+
+    Ok(values)
+}
+
+// journalctl without pcre2 support refuses --grep, then the window is
+// fetched as is and matched here instead
+pub async fn search_journal(pattern: &str, since: &str) -> Result<Vec<JournalLog>> {
+    info!("search_journal called: {} since {}", pattern, since);
+
+    let out = Command::new("sudo")
+        .arg("journalctl")
+        .arg(format!("--grep={}", pattern))
+        .arg("--case-sensitive=false")
+        .arg(format!("--since={}", since))
+        .arg("-r")
+        .arg("-n")
+        .arg(SEARCH_LIMIT.to_string())
+        .arg("-o")
+        .arg("json")
+        .output()
+        .await?;
+
+    if out.status.success() {
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        return Ok(stdout
+            .lines()
+            .filter_map(|line| parse_log(line, &7))
+            .collect());
+    }
+
+    // no matches is reported with a non-zero exit code and empty stderr
+    if out.stderr.is_empty() {
+        return Ok(vec![]);
+    }
+
+    info!(
+        "journalctl --grep failed, matching in-process: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    // the pattern is a regular expression for --grep too
+    let matcher = RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| RounalError::JournalCtlError(format!("search {}, {}", pattern, e)))?;
+
+    let out = Command::new("sudo")
+        .arg("journalctl")
+        .arg(format!("--since={}", since))
+        .arg("-r")
+        .arg("-n")
+        .arg(SEARCH_SCAN_LIMIT.to_string())
+        .arg("-o")
+        .arg("json")
+        .output()
+        .await?;
+
+    if !out.status.success() {
+        return Err(RounalError::JournalCtlError(format!(
+            "search {}, {}",
+            pattern,
+            String::from_utf8_lossy(&out.stderr)
+        )));
+    }
+
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    Ok(stdout
+        .lines()
+        .filter_map(|line| parse_log(line, &7))
+        .filter(|log| matcher.is_match(&log.log_message))
+        .take(SEARCH_LIMIT)
+        .collect())
+}
+
+// MESSAGE and other fields are arrays of bytes when they are not valid utf-8
+fn get_field(entry: &Value, field: &str) -> Option<String> {
+    match entry.get(field)? {
+        Value::String(s) => Some(s.to_string()),
+        Value::Array(bytes) => {
+            let bytes: Vec<u8> = bytes
+                .iter()
+                .filter_map(|b| b.as_u64().map(|b| b as u8))
+                .collect();
+            Some(String::from_utf8_lossy(&bytes).to_string())
+        }
+        other => Some(other.to_string()),
+    }
+}
+
+pub fn format_timestamp(realtime: u64) -> String {
+    Local
+        .timestamp_micros(realtime as i64)
+        .single()
+        .map(|time| time.format("%b %d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+// "Main process exited, code=exited, status=1/FAILURE" also comes with
+// EXIT_CODE and EXIT_STATUS fields on newer systemd
+fn get_exit_status(entry: &Value, log_message: &str) -> String {
+    if let Some(code) = get_field(entry, "EXIT_CODE") {
+        return match get_field(entry, "EXIT_STATUS") {
+            Some(status) => format!("code={}, status={}", code, status),
+            None => format!("code={}", code),
+        };
+    }
+
+    log_message
+        .strip_prefix("Main process exited, ")
+        .map(|status| status.trim_end_matches('.').to_string())
+        .unwrap_or_default()
+}
+
 fn parse_log(log_line: &str, p: &u8) -> Option<JournalLog> {
-    let parts: Vec<&str> = log_line.split_whitespace().collect();
+    let entry: Value = serde_json::from_str(log_line).ok()?;
 
     match p {
         1..=7 => {
-            let priority = *p;
-            let timestamp = parts.get(..3)?.join(" ");
-            let hostname = parts.get(3)?.to_string();
-            let service = parts.get(4)?.trim_end_matches(":").to_string();
-            let log_message = parts.get(5..)?.join(" ").to_string();
+            let priority = get_field(&entry, "PRIORITY")
+                .and_then(|p| p.parse::<u8>().ok())
+                .unwrap_or(*p);
+            let realtime = get_field(&entry, "__REALTIME_TIMESTAMP")?
+                .parse::<u64>()
+                .ok()?;
+            let timestamp = format_timestamp(realtime);
+            let hostname = get_field(&entry, "_HOSTNAME").unwrap_or_default();
+            let unit = get_field(&entry, "_SYSTEMD_UNIT").unwrap_or_default();
+            let service = get_field(&entry, "SYSLOG_IDENTIFIER")
+                .or_else(|| get_field(&entry, "_COMM"))
+                .unwrap_or_else(|| unit.clone());
+            let log_message = get_field(&entry, "MESSAGE").unwrap_or_default();
+            let cursor = get_field(&entry, "__CURSOR").unwrap_or_default();
+            // messages of systemd itself about the unit carry INVOCATION_ID
+            let invocation = get_field(&entry, "_SYSTEMD_INVOCATION_ID")
+                .or_else(|| get_field(&entry, "INVOCATION_ID"))
+                .unwrap_or_default();
+            let exit_status = get_exit_status(&entry, &log_message);
 
             Some(JournalLog {
                 priority,
@@ -120,6 +538,11 @@ fn parse_log(log_line: &str, p: &u8) -> Option<JournalLog> {
                 log_message,
                 hostname,
                 service,
+                unit,
+                cursor,
+                realtime,
+                invocation,
+                exit_status,
             })
         }
         _ => None,
@@ -0,0 +1,142 @@
+use crate::core::journal::JournalLog;
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, TimeZone};
+
+pub const JUMP_EXAMPLES: &str = "14:32, 2025-01-02 14:32:10, yesterday 08:00, -15m, 2h ago";
+
+// "14:32" and "14:32:10" are today, a date or today / yesterday can come
+// before them, relative times count back from now
+pub fn parse_jump_time(input: &str, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    let input = input.trim().to_lowercase();
+    if input == "now" {
+        return Some(now);
+    }
+    if let Some(offset) = parse_relative_time(&input) {
+        return now.checked_sub_signed(offset);
+    }
+
+    let (date, time) = match input.split_once(char::is_whitespace) {
+        Some((date, time)) => (Some(date), Some(time.trim())),
+        None if input.contains(':') => (None, Some(input.as_str())),
+        None => (Some(input.as_str()), None),
+    };
+    let date = match date {
+        None | Some("today") => now.date_naive(),
+        Some("yesterday") => now.date_naive().pred_opt()?,
+        Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?,
+    };
+    let time = match time {
+        None => NaiveTime::MIN,
+        Some(time) => NaiveTime::parse_from_str(time, "%H:%M:%S")
+            .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+            .ok()?,
+    };
+
+    Local.from_local_datetime(&date.and_time(time)).earliest()
+}
+
+// "-15m" or "15m ago", in s, m, h, d or w
+fn parse_relative_time(input: &str) -> Option<Duration> {
+    let amount = input
+        .strip_prefix('-')
+        .or_else(|| input.strip_suffix("ago"))?
+        .trim();
+    let (number, unit) = amount.split_at(amount.find(|c: char| !c.is_ascii_digit())?);
+    let number: i64 = number.parse().ok()?;
+
+    let seconds = match unit.trim() {
+        "s" | "sec" => 1,
+        "m" | "min" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+    Duration::try_seconds(number.checked_mul(seconds)?)
+}
+
+// Entries come newest first, so the ones after the time are the front of
+// the list. The closer of the two entries around the time is taken.
+pub fn find_nearest_entry(entries: &[JournalLog], realtime: u64) -> Option<usize> {
+    let idx = entries.partition_point(|log| log.realtime > realtime);
+    [idx.checked_sub(1), Some(idx)]
+        .into_iter()
+        .flatten()
+        .filter(|idx| *idx < entries.len())
+        .min_by_key(|idx| entries[*idx].realtime.abs_diff(realtime))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 1, 2, 12, 0, 0).unwrap()
+    }
+
+    fn at(date: (i32, u32, u32), time: (u32, u32, u32)) -> Option<DateTime<Local>> {
+        Local
+            .with_ymd_and_hms(date.0, date.1, date.2, time.0, time.1, time.2)
+            .single()
+    }
+
+    #[test]
+    fn parses_times_of_today_and_dates() {
+        assert_eq!(
+            parse_jump_time("14:32", now()),
+            at((2025, 1, 2), (14, 32, 0))
+        );
+        assert_eq!(
+            parse_jump_time(" 2024-12-31 23:59:10 ", now()),
+            at((2024, 12, 31), (23, 59, 10))
+        );
+        assert_eq!(
+            parse_jump_time("Yesterday 08:00", now()),
+            at((2025, 1, 1), (8, 0, 0))
+        );
+        assert_eq!(
+            parse_jump_time("2024-06-01", now()),
+            at((2024, 6, 1), (0, 0, 0))
+        );
+        assert_eq!(parse_jump_time("now", now()), Some(now()));
+    }
+
+    #[test]
+    fn parses_relative_times() {
+        assert_eq!(
+            parse_jump_time("-15m", now()),
+            at((2025, 1, 2), (11, 45, 0))
+        );
+        assert_eq!(
+            parse_jump_time("2h ago", now()),
+            at((2025, 1, 2), (10, 0, 0))
+        );
+        assert_eq!(
+            parse_jump_time("1d ago", now()),
+            at((2025, 1, 1), (12, 0, 0))
+        );
+    }
+
+    #[test]
+    fn rejects_other_input() {
+        for input in ["", "bogus", "25:00", "-15x", "2025-13-01", "tomorrow 08:00"] {
+            assert_eq!(parse_jump_time(input, now()), None, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn finds_the_closest_entry() {
+        let entries: Vec<JournalLog> = [50, 40, 30, 20]
+            .into_iter()
+            .map(|realtime| JournalLog {
+                realtime,
+                ..Default::default()
+            })
+            .collect();
+
+        assert_eq!(find_nearest_entry(&entries, 41), Some(1));
+        assert_eq!(find_nearest_entry(&entries, 34), Some(2));
+        assert_eq!(find_nearest_entry(&entries, 100), Some(0));
+        assert_eq!(find_nearest_entry(&entries, 0), Some(3));
+        assert_eq!(find_nearest_entry(&[], 10), None);
+    }
+}
@@ -0,0 +1,906 @@
+use crate::core::error::{Result, RounalError};
+use crate::core::filter::QUICK_FILTERS;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+use std::{collections::HashMap, fmt};
+
+// the views whose keys go through the keymap, popups share the moves,
+// open and quit, prompts keep their own keys
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyContext {
+    Services,
+    Logs,
+    Dashboard,
+    Popup,
+}
+
+use KeyContext::{Dashboard as D, Logs as L, Popup as P, Services as S};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Quit,
+    Help,
+    MoveDown,
+    MoveUp,
+    MoveLeft,
+    MoveRight,
+    Top,
+    Bottom,
+    HalfPageDown,
+    HalfPageUp,
+    PageDown,
+    PageUp,
+    ScreenTop,
+    ScreenMiddle,
+    ScreenBottom,
+    SetMark,
+    JumpToMark,
+    Open,
+    Search,
+    Command,
+    GlobalSearch,
+    FindService,
+    Filters,
+    SortColumn,
+    SortDirection,
+    ScrollLeft,
+    ScrollRight,
+    SwitchPane,
+    ShowLine,
+    Docs,
+    Dashboard,
+    RefreshDashboard,
+    OpenInTab,
+    CloseTab,
+    PrevTab,
+    NextTab,
+    MarkUnit,
+    OpenMarked,
+    CloseLogs,
+    Yank,
+    VisualSelection,
+    Bookmark,
+    Annotate,
+    Bookmarks,
+    Export,
+    Bundle,
+    MatchBuilder,
+    PrevInvocation,
+    NextInvocation,
+    FoldInvocation,
+    FoldOldInvocations,
+    ToggleInvocations,
+    Histogram,
+    JumpToTime,
+    Patterns,
+    CollapseDuplicates,
+    ExpandDuplicates,
+}
+
+// name in the [keys] config section, help text, default keys, views
+type ActionSpec = (
+    Action,
+    &'static str,
+    &'static str,
+    &'static [&'static str],
+    &'static [KeyContext],
+);
+
+const ACTIONS: &[ActionSpec] = &[
+    (
+        Action::MoveDown,
+        "move_down",
+        "Move down",
+        &["j", "Down"],
+        &[S, L, D, P],
+    ),
+    (
+        Action::MoveUp,
+        "move_up",
+        "Move up",
+        &["k", "Up"],
+        &[S, L, D, P],
+    ),
+    (
+        Action::MoveLeft,
+        "move_left",
+        "Previous priority / view",
+        &["h", "Left"],
+        &[S, L, P],
+    ),
+    (
+        Action::MoveRight,
+        "move_right",
+        "Next priority / view",
+        &["l", "Right"],
+        &[S, L, D, P],
+    ),
+    (
+        Action::Top,
+        "top",
+        "First line / line N",
+        &["g g", "Home"],
+        &[S, L],
+    ),
+    (
+        Action::Bottom,
+        "bottom",
+        "Last line / line N",
+        &["G", "End"],
+        &[S, L],
+    ),
+    (
+        Action::HalfPageDown,
+        "half_page_down",
+        "Half page down",
+        &["ctrl+d"],
+        &[S, L],
+    ),
+    (
+        Action::HalfPageUp,
+        "half_page_up",
+        "Half page up",
+        &["ctrl+u"],
+        &[S, L],
+    ),
+    (
+        Action::PageDown,
+        "page_down",
+        "Page down",
+        &["ctrl+f", "PageDown"],
+        &[S, L],
+    ),
+    (
+        Action::PageUp,
+        "page_up",
+        "Page up",
+        &["ctrl+b", "PageUp"],
+        &[S, L],
+    ),
+    (
+        Action::ScreenTop,
+        "screen_top",
+        "Top of the screen",
+        &["H"],
+        &[S, L],
+    ),
+    (
+        Action::ScreenMiddle,
+        "screen_middle",
+        "Middle of the screen",
+        &["M"],
+        &[L],
+    ),
+    (
+        Action::ScreenBottom,
+        "screen_bottom",
+        "Bottom of the screen",
+        &["L"],
+        &[S, L],
+    ),
+    (Action::SetMark, "set_mark", "Set mark a-z", &["m"], &[L]),
+    (
+        Action::JumpToMark,
+        "jump_to_mark",
+        "Jump to mark a-z",
+        &["'"],
+        &[L],
+    ),
+    (Action::Open, "open", "Select", &["Enter"], &[S, D, P]),
+    (Action::CloseLogs, "close_logs", "Close logs", &["c"], &[L]),
+    (
+        Action::ShowLine,
+        "show_line",
+        "Toggle see line in a modal",
+        &["K"],
+        &[S, L],
+    ),
+    (Action::Yank, "yank", "Yank message", &["y"], &[L]),
+    (
+        Action::VisualSelection,
+        "visual_selection",
+        "Visual line selection",
+        &["V"],
+        &[L],
+    ),
+    (Action::Bookmark, "bookmark", "Bookmark entry", &["b"], &[L]),
+    (
+        Action::Annotate,
+        "annotate",
+        "Add a note to an entry",
+        &["a"],
+        &[L],
+    ),
+    (
+        Action::Bookmarks,
+        "bookmarks",
+        "List bookmarks",
+        &["B"],
+        &[S, L],
+    ),
+    (Action::Search, "search", "Begin search", &["/"], &[S, L]),
+    (
+        Action::Command,
+        "command",
+        "Command line",
+        &[":"],
+        &[S, L, D],
+    ),
+    (
+        Action::GlobalSearch,
+        "global_search",
+        "Search all services",
+        &["S"],
+        &[S, L, D],
+    ),
+    (
+        Action::FindService,
+        "find_service",
+        "Find service",
+        &["f"],
+        &[S, D],
+    ),
+    (
+        Action::Filters,
+        "filters",
+        "Filter units by state",
+        &["F"],
+        &[S],
+    ),
+    (
+        Action::SortColumn,
+        "sort_column",
+        "Sort by column",
+        &["o"],
+        &[S],
+    ),
+    (
+        Action::SortDirection,
+        "sort_direction",
+        "Reverse sort",
+        &["O"],
+        &[S],
+    ),
+    (
+        Action::ScrollLeft,
+        "scroll_left",
+        "Scroll long rows left",
+        &["<"],
+        &[S, L],
+    ),
+    (
+        Action::ScrollRight,
+        "scroll_right",
+        "Scroll long rows right",
+        &[">"],
+        &[S, L],
+    ),
+    (
+        Action::SwitchPane,
+        "switch_pane",
+        "Switch pane in split layout",
+        &["Tab"],
+        &[S, L, D],
+    ),
+    (
+        Action::OpenInTab,
+        "open_in_tab",
+        "Open in background tab",
+        &["T"],
+        &[S],
+    ),
+    (Action::CloseTab, "close_tab", "Close tab", &["X"], &[L]),
+    (Action::PrevTab, "prev_tab", "Previous tab", &["{"], &[S, L]),
+    (Action::NextTab, "next_tab", "Next tab", &["}"], &[S, L]),
+    (Action::MarkUnit, "mark_unit", "Mark unit", &["Space"], &[S]),
+    (
+        Action::OpenMarked,
+        "open_marked",
+        "Open marked units merged",
+        &["M"],
+        &[S],
+    ),
+    (
+        Action::MatchBuilder,
+        "match_builder",
+        "Match journal fields in logs",
+        &["="],
+        &[L],
+    ),
+    (
+        Action::PrevInvocation,
+        "prev_invocation",
+        "Previous invocation",
+        &["["],
+        &[L],
+    ),
+    (
+        Action::NextInvocation,
+        "next_invocation",
+        "Next invocation",
+        &["]"],
+        &[L],
+    ),
+    (
+        Action::FoldInvocation,
+        "fold_invocation",
+        "Fold invocation",
+        &["z"],
+        &[L],
+    ),
+    (
+        Action::FoldOldInvocations,
+        "fold_old_invocations",
+        "Fold all but newest",
+        &["Z"],
+        &[L],
+    ),
+    (
+        Action::ToggleInvocations,
+        "toggle_invocations",
+        "Toggle invocation grouping",
+        &["i"],
+        &[L],
+    ),
+    (
+        Action::Histogram,
+        "histogram",
+        "Select time in log volume chart",
+        &["#"],
+        &[L],
+    ),
+    (
+        Action::JumpToTime,
+        "jump_to_time",
+        "Jump to a time",
+        &["t"],
+        &[L],
+    ),
+    (
+        Action::Patterns,
+        "patterns",
+        "Repeated message patterns",
+        &["P"],
+        &[L],
+    ),
+    (
+        Action::CollapseDuplicates,
+        "collapse_duplicates",
+        "Collapse repeated lines",
+        &["u"],
+        &[L],
+    ),
+    (
+        Action::ExpandDuplicates,
+        "expand_duplicates",
+        "Expand repeated lines",
+        &["e"],
+        &[L],
+    ),
+    (
+        Action::Export,
+        "export",
+        "Export logs to a file",
+        &["w"],
+        &[L],
+    ),
+    (
+        Action::Bundle,
+        "bundle",
+        "Diagnostic bundle of a unit",
+        &["R"],
+        &[S, L],
+    ),
+    (Action::Dashboard, "dashboard", "Dashboard", &["D"], &[S, D]),
+    (
+        Action::RefreshDashboard,
+        "refresh_dashboard",
+        "Refresh dashboard",
+        &["r"],
+        &[D],
+    ),
+    (
+        Action::Docs,
+        "docs",
+        "Toggle read explanations",
+        &["E"],
+        &[S, D],
+    ),
+    (Action::Quit, "quit", "Quit", &["q", "Esc"], &[S, L, D, P]),
+    (Action::Help, "help", "Toggle help", &["?"], &[S, L, D]),
+];
+
+// the actions that open a popup also close it
+const POPUP_TOGGLES: &[Action] = &[
+    Action::Filters,
+    Action::MatchBuilder,
+    Action::Patterns,
+    Action::Bookmarks,
+    Action::Histogram,
+    Action::GlobalSearch,
+];
+
+// clear, annotate, delete, toggle a match and switch fields in the popups
+const POPUP_KEYS: &[&str] = &["c", "a", "d", "Space", "Tab", "BackTab"];
+
+// the filters popup also takes the quick filter letters, only quit, open
+// and filters itself are read there
+fn is_popup_key(action: Action, chord: &KeyChord) -> bool {
+    let is_quick_filter = matches!(action, Action::Quit | Action::Open | Action::Filters)
+        && chord.modifiers.is_empty()
+        && QUICK_FILTERS
+            .iter()
+            .any(|(hotkey, _)| chord.code == KeyCode::Char(*hotkey));
+    is_quick_filter
+        || POPUP_KEYS
+            .iter()
+            .any(|key| KeyChord::parse(key).as_ref() == Some(chord))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    // "j", "K", "Enter", "ctrl+d", "shift+Tab", the key comes last
+    pub fn parse(chord: &str) -> Option<Self> {
+        let mut parts: Vec<&str> = chord.split('+').collect();
+        // "+" and "ctrl++" bind the plus key itself
+        if chord.ends_with("++") || chord == "+" {
+            parts.truncate(parts.len().saturating_sub(2));
+            parts.push("+");
+        }
+
+        let key = parts.pop()?;
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in parts {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return None,
+            };
+        }
+
+        let code = match key.to_lowercase().as_str() {
+            _ if key.chars().count() == 1 => KeyCode::Char(key.chars().next()?),
+            "enter" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "space" => KeyCode::Char(' '),
+            "backspace" => KeyCode::Backspace,
+            "delete" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            name => KeyCode::F(name.strip_prefix('f')?.parse().ok()?),
+        };
+
+        Some(Self::normalize(code, modifiers))
+    }
+
+    // Shift is part of the character itself and of BackTab, terminals differ
+    // in whether they report it as a modifier too.
+    fn normalize(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            code => code,
+        };
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+
+        Self { code, modifiers }
+    }
+
+    pub fn from_event(key: &KeyEvent) -> Self {
+        Self::normalize(key.code, key.modifiers)
+    }
+
+    pub fn is_count_digit(&self) -> bool {
+        self.modifiers.is_empty() && matches!(self.code, KeyCode::Char('1'..='9'))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+// a single key or a list of keys for an action
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum KeyBinding {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyBinding {
+    fn get_chords(&self) -> Vec<&str> {
+        match self {
+            Self::One(chord) => vec![chord.as_str()],
+            Self::Many(chords) => chords.iter().map(|chord| chord.as_str()).collect(),
+        }
+    }
+}
+
+// a key or a sequence of keys like "g g"
+type KeySequence = Vec<KeyChord>;
+
+fn format_sequence(sequence: &[KeyChord]) -> String {
+    let keys: Vec<String> = sequence.iter().map(|chord| chord.to_string()).collect();
+    if keys.iter().all(|key| key.chars().count() == 1) {
+        keys.concat()
+    } else {
+        keys.join(" ")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyMatch {
+    Action(Action),
+    // the keys so far start a longer sequence
+    Prefix,
+    None,
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<KeySequence>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&HashMap::new()).expect("Default key bindings are valid")
+    }
+}
+
+impl Keymap {
+    // The [keys] section replaces the default keys of the actions it names.
+    // Unknown actions, unparsable keys and a key bound to two actions of the
+    // same view are errors, so is a key that starts a sequence of another.
+    pub fn new(keys: &HashMap<String, KeyBinding>) -> Result<Self> {
+        if let Some(name) = keys
+            .keys()
+            .find(|name| !ACTIONS.iter().any(|spec| spec.1 == name.as_str()))
+        {
+            return Err(RounalError::KeymapError(format!("unknown action {}", name)));
+        }
+
+        let mut bindings = vec![];
+        for (action, name, _, defaults, _) in ACTIONS {
+            let chords = match keys.get(*name) {
+                Some(binding) => binding.get_chords(),
+                None => defaults.to_vec(),
+            };
+
+            let sequences = chords
+                .iter()
+                .map(|chord| {
+                    chord
+                        .split_whitespace()
+                        .map(KeyChord::parse)
+                        .collect::<Option<KeySequence>>()
+                        .filter(|sequence| !sequence.is_empty())
+                        .ok_or_else(|| {
+                            RounalError::KeymapError(format!(
+                                "invalid key {:?} for {}",
+                                chord, name
+                            ))
+                        })
+                })
+                .collect::<Result<Vec<KeySequence>>>()?;
+            bindings.push((*action, sequences));
+        }
+
+        let keymap = Self { bindings };
+        keymap.check_conflicts()?;
+        Ok(keymap)
+    }
+
+    fn check_conflicts(&self) -> Result<()> {
+        for (idx, (action, sequences)) in self.bindings.iter().enumerate() {
+            // digits start counts in the lists and switch the priority in the logs
+            let has_counts = get_spec(*action)
+                .4
+                .iter()
+                .any(|context| matches!(context, S | L));
+            if let Some(sequence) = sequences
+                .iter()
+                .find(|sequence| has_counts && sequence[0].is_count_digit())
+            {
+                return Err(RounalError::KeymapError(format!(
+                    "{} of {} overlaps the counts and the priorities 1-7",
+                    format_sequence(sequence),
+                    get_spec(*action).1
+                )));
+            }
+
+            let is_in_popups = get_spec(*action).4.contains(&P) || POPUP_TOGGLES.contains(action);
+            if let Some(sequence) = sequences
+                .iter()
+                .find(|sequence| is_in_popups && is_popup_key(*action, &sequence[0]))
+            {
+                return Err(RounalError::KeymapError(format!(
+                    "{} of {} is a key of the popups",
+                    format_sequence(sequence),
+                    get_spec(*action).1
+                )));
+            }
+
+            for (other, other_sequences) in &self.bindings[idx + 1..] {
+                let shares_view = get_spec(*action)
+                    .4
+                    .iter()
+                    .any(|context| get_spec(*other).4.contains(context));
+                if !shares_view {
+                    continue;
+                }
+
+                for sequence in sequences {
+                    if let Some(other_sequence) = other_sequences.iter().find(|other_sequence| {
+                        sequence.starts_with(other_sequence) || other_sequence.starts_with(sequence)
+                    }) {
+                        return Err(RounalError::KeymapError(format!(
+                            "{} of {} overlaps {} of {}",
+                            format_sequence(sequence),
+                            get_spec(*action).1,
+                            format_sequence(other_sequence),
+                            get_spec(*other).1
+                        )));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // the keys pressed so far, the handler keeps them while they are a prefix
+    pub fn match_keys(&self, context: KeyContext, keys: &[KeyChord]) -> KeyMatch {
+        let mut result = KeyMatch::None;
+        for (action, sequences) in &self.bindings {
+            if !get_spec(*action).4.contains(&context) {
+                continue;
+            }
+
+            for sequence in sequences {
+                if sequence.as_slice() == keys {
+                    return KeyMatch::Action(*action);
+                }
+                if sequence.starts_with(keys) {
+                    result = KeyMatch::Prefix;
+                }
+            }
+        }
+        result
+    }
+
+    // single keys only, for views without sequences
+    pub fn get_action(&self, context: KeyContext, key: &KeyEvent) -> Option<Action> {
+        match self.match_keys(context, &[KeyChord::from_event(key)]) {
+            KeyMatch::Action(action) => Some(action),
+            _ => None,
+        }
+    }
+
+    pub fn get_keys(&self, action: Action) -> String {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == action)
+            .map(|(_, sequences)| {
+                sequences
+                    .iter()
+                    .map(|sequence| format_sequence(sequence))
+                    .collect::<Vec<String>>()
+                    .join(" / ")
+            })
+            .unwrap_or_default()
+    }
+
+    // the lines of the help modal, in the order of the action table
+    pub fn get_help_lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .bindings
+            .iter()
+            .filter(|(_, sequences)| !sequences.is_empty())
+            .map(|(action, _)| format!("{}: [{}]", get_spec(*action).2, self.get_keys(*action)))
+            .collect();
+        lines.insert(lines.len().min(4), "Change priority: [1-7]".to_string());
+        lines
+    }
+}
+
+// the names of the [keys] section, also commands of the command line
+pub fn get_action_names() -> impl Iterator<Item = &'static str> {
+    ACTIONS.iter().map(|spec| spec.1)
+}
+
+pub fn find_action(name: &str) -> Option<Action> {
+    ACTIONS
+        .iter()
+        .find(|spec| spec.1 == name)
+        .map(|spec| spec.0)
+}
+
+pub fn is_available(action: Action, context: KeyContext) -> bool {
+    get_spec(action).4.contains(&context)
+}
+
+pub fn get_action_name(action: Action) -> &'static str {
+    get_spec(action).1
+}
+
+fn get_spec(action: Action) -> &'static ActionSpec {
+    ACTIONS
+        .iter()
+        .find(|spec| spec.0 == action)
+        .expect("Every action has a spec")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(code: KeyCode, modifiers: KeyModifiers) -> Option<KeyChord> {
+        Some(KeyChord { code, modifiers })
+    }
+
+    fn keys(sequence: &str) -> Vec<KeyChord> {
+        sequence
+            .split_whitespace()
+            .map(|key| KeyChord::parse(key).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn parses_chords() {
+        assert_eq!(
+            KeyChord::parse("j"),
+            chord(KeyCode::Char('j'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            KeyChord::parse("ctrl+d"),
+            chord(KeyCode::Char('d'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            KeyChord::parse("Ctrl+Alt+PageDown"),
+            chord(KeyCode::PageDown, KeyModifiers::CONTROL | KeyModifiers::ALT)
+        );
+        assert_eq!(
+            KeyChord::parse("F5"),
+            chord(KeyCode::F(5), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            KeyChord::parse("Space"),
+            chord(KeyCode::Char(' '), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            KeyChord::parse("ctrl++"),
+            chord(KeyCode::Char('+'), KeyModifiers::CONTROL)
+        );
+    }
+
+    #[test]
+    fn shift_is_folded_into_characters_and_backtab() {
+        assert_eq!(KeyChord::parse("shift+k"), KeyChord::parse("K"));
+        assert_eq!(
+            KeyChord::parse("shift+Tab"),
+            chord(KeyCode::BackTab, KeyModifiers::NONE)
+        );
+        assert_eq!(
+            KeyChord::from_event(&KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT)),
+            KeyChord::parse("G").unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        for chord in ["", "meta+j", "ctrl+", "Pgdn", "Fx", "F99999"] {
+            assert_eq!(KeyChord::parse(chord), None, "{:?}", chord);
+        }
+    }
+
+    #[test]
+    fn matches_sequences_and_prefixes() {
+        let keymap = Keymap::new(&HashMap::new()).unwrap();
+
+        assert_eq!(
+            keymap.match_keys(L, &keys("j")),
+            KeyMatch::Action(Action::MoveDown)
+        );
+        assert_eq!(keymap.match_keys(L, &keys("g")), KeyMatch::Prefix);
+        assert_eq!(
+            keymap.match_keys(L, &keys("g g")),
+            KeyMatch::Action(Action::Top)
+        );
+        assert_eq!(keymap.match_keys(L, &keys("g x")), KeyMatch::None);
+        // the chart is only in the logs
+        assert_eq!(keymap.match_keys(S, &keys("#")), KeyMatch::None);
+    }
+
+    #[test]
+    fn rebinding_replaces_the_defaults() {
+        let keys_config = HashMap::from([(
+            "move_down".to_string(),
+            KeyBinding::Many(vec!["n".to_string(), "ctrl+n".to_string()]),
+        )]);
+        let keymap = Keymap::new(&keys_config).unwrap();
+
+        assert_eq!(
+            keymap.match_keys(S, &keys("ctrl+n")),
+            KeyMatch::Action(Action::MoveDown)
+        );
+        assert_eq!(keymap.match_keys(S, &keys("j")), KeyMatch::None);
+    }
+
+    #[test]
+    fn popups_follow_rebinding() {
+        let keys_config = HashMap::from([("quit".to_string(), KeyBinding::One("v".to_string()))]);
+        let keymap = Keymap::new(&keys_config).unwrap();
+
+        assert_eq!(
+            keymap.match_keys(P, &keys("v")),
+            KeyMatch::Action(Action::Quit)
+        );
+        assert_eq!(keymap.match_keys(P, &keys("q")), KeyMatch::None);
+        // only the moves, open and quit are shared
+        assert_eq!(keymap.match_keys(P, &keys("y")), KeyMatch::None);
+    }
+
+    #[test]
+    fn help_lines_list_the_priorities() {
+        let keymap = Keymap::new(&HashMap::new()).unwrap();
+        assert!(keymap
+            .get_help_lines()
+            .contains(&"Change priority: [1-7]".to_string()));
+    }
+
+    #[test]
+    fn reports_conflicts() {
+        for (action, key) in [
+            ("yank", "j"),
+            ("yank", "g"),
+            ("quit", "3"),
+            ("move_down", "d"),
+            ("patterns", "Space"),
+            ("quit", "x"),
+        ] {
+            let keys_config =
+                HashMap::from([(action.to_string(), KeyBinding::One(key.to_string()))]);
+            assert!(Keymap::new(&keys_config).is_err(), "{} = {}", action, key);
+        }
+    }
+}
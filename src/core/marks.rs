@@ -0,0 +1,29 @@
+use crate::{
+    core::error::{Result, RounalError},
+    util::{expand_home, write_file_atomic},
+};
+use std::collections::HashMap;
+
+// the letter of a mark, with the priority and journal cursor of its entry
+pub type ServiceMarks = HashMap<char, (u8, String)>;
+
+// a missing file is no marks yet
+pub async fn load_marks(path: &str) -> Result<HashMap<String, ServiceMarks>> {
+    let path = expand_home(path);
+    match tokio::fs::read_to_string(&path).await {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| RounalError::MarkError(format!("{}: {}", path, e))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(e) => Err(RounalError::MarkError(format!("{}: {}", path, e))),
+    }
+}
+
+pub async fn save_marks(path: &str, marks: &HashMap<String, ServiceMarks>) -> Result<()> {
+    let path = expand_home(path);
+    let content =
+        serde_json::to_string_pretty(marks).map_err(|e| RounalError::MarkError(e.to_string()))?;
+
+    write_file_atomic(&path, &content)
+        .await
+        .map_err(|e| RounalError::MarkError(format!("{}: {}", path, e)))
+}
@@ -0,0 +1,163 @@
+use crate::core::{error::Result, journal::get_field_values};
+use log::error;
+use tokio::task::JoinHandle;
+
+// fields offered by the match builder, any other field can not be added
+pub const MATCH_FIELDS: [&str; 7] = [
+    "_COMM",
+    "SYSLOG_IDENTIFIER",
+    "_PID",
+    "_UID",
+    "_EXE",
+    "_TRANSPORT",
+    "_HOSTNAME",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalMatch {
+    pub field: String,
+    pub value: String,
+}
+
+impl JournalMatch {
+    pub fn new(field: &str, value: &str) -> Self {
+        Self {
+            field: field.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    // the form journalctl takes as an argument
+    pub fn label(&self) -> String {
+        format!("{}={}", self.field, self.value)
+    }
+}
+
+#[derive(Debug)]
+pub struct MatchBuilder {
+    pub field: usize,
+    pub values: Vec<String>,
+    pub error: Option<String>,
+    pub current_line: usize,
+    pub is_changed: bool,
+    pub loading: Option<JoinHandle<Result<Vec<String>>>>,
+}
+
+impl MatchBuilder {
+    pub fn new() -> Self {
+        Self {
+            field: 0,
+            values: vec![],
+            error: None,
+            current_line: 0,
+            is_changed: false,
+            loading: None,
+        }
+    }
+
+    pub fn get_field(&self) -> &'static str {
+        MATCH_FIELDS[self.field]
+    }
+
+    pub fn next_field(&mut self) {
+        self.select_field((self.field + 1) % MATCH_FIELDS.len());
+    }
+
+    pub fn prev_field(&mut self) {
+        self.select_field((self.field + MATCH_FIELDS.len() - 1) % MATCH_FIELDS.len());
+    }
+
+    fn select_field(&mut self, field: usize) {
+        self.cancel_loading();
+        self.field = field;
+        self.values.clear();
+        self.error = None;
+        self.current_line = 0;
+    }
+
+    pub fn set_values(&mut self, values: Vec<String>) {
+        self.values = values;
+        self.error = None;
+        self.current_line = 0;
+    }
+
+    pub fn set_error(&mut self, error: String) {
+        self.values.clear();
+        self.error = Some(error);
+    }
+
+    // journalctl -F reads the whole journal, the modal stays usable meanwhile
+    pub fn load_values_in_background(&mut self, units: &[String]) {
+        self.cancel_loading();
+        let field = self.get_field();
+        let units = units.to_vec();
+        self.loading = Some(tokio::spawn(async move {
+            get_field_values(field, &units).await
+        }));
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.loading.is_some()
+    }
+
+    pub fn cancel_loading(&mut self) {
+        if let Some(handle) = self.loading.take() {
+            handle.abort();
+        }
+    }
+
+    pub async fn poll_loading(&mut self) {
+        if !self
+            .loading
+            .as_ref()
+            .is_some_and(|handle| handle.is_finished())
+        {
+            return;
+        }
+
+        let Some(handle) = self.loading.take() else {
+            return;
+        };
+
+        match handle.await {
+            Ok(Ok(values)) => self.set_values(values),
+            Ok(Err(e)) => {
+                error!("Getting values of {} failed: {}", self.get_field(), e);
+                self.set_error(e.to_string());
+            }
+            Err(e) => error!("Loading task for {} failed: {}", self.get_field(), e),
+        }
+    }
+
+    pub fn get_selected(&self) -> Option<JournalMatch> {
+        self.values
+            .get(self.current_line)
+            .map(|value| JournalMatch::new(self.get_field(), value))
+    }
+
+    pub fn move_cursor_down(&mut self) {
+        if self.current_line < self.values.len().saturating_sub(1) {
+            self.current_line += 1;
+        }
+    }
+
+    pub fn move_cursor_up(&mut self) {
+        if self.current_line > 0 {
+            self.current_line -= 1;
+        }
+    }
+}
+
+impl Default for MatchBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn toggle_match(matches: &mut Vec<JournalMatch>, journal_match: JournalMatch) {
+    if let Some(idx) = matches.iter().position(|m| m == &journal_match) {
+        matches.remove(idx);
+    } else {
+        matches.push(journal_match);
+    }
+}
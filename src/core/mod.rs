@@ -1,6 +1,26 @@
+pub mod bookmarks;
+pub mod bundle;
 pub mod clipboard;
+pub mod command;
 pub mod config;
+pub mod dashboard;
+pub mod dedupe;
 pub mod error;
+pub mod export;
+pub mod filter;
+pub mod fuzzy;
+pub mod histogram;
 pub mod input_handler;
+pub mod invocation;
 pub mod journal;
+pub mod jump;
+pub mod keymap;
+pub mod marks;
+pub mod matches;
+pub mod motion;
+pub mod mouse;
+pub mod patterns;
+pub mod search;
+pub mod sort;
 pub mod system;
+pub mod tabs;
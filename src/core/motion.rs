@@ -0,0 +1,283 @@
+use crate::core::keymap::{Action, KeyChord};
+use std::time::{Duration, Instant};
+
+// a count nothing follows is dropped after a while
+pub const PENDING_KEYS_TIMEOUT: Duration = Duration::from_millis(600);
+
+// The rows of a table on screen, written while drawing. Entries are line
+// numbers, rows also count the separators of invocations.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Viewport {
+    pub offset: usize,
+    pub height: usize,
+    pub first: Option<usize>,
+    pub last: Option<usize>,
+    pub h_scroll_limit: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Motion {
+    Down,
+    Up,
+    Top,
+    Bottom,
+    HalfPageDown,
+    HalfPageUp,
+    PageDown,
+    PageUp,
+    ScreenTop,
+    ScreenMiddle,
+    ScreenBottom,
+}
+
+impl Motion {
+    pub fn from_action(action: Action) -> Option<Self> {
+        match action {
+            Action::MoveDown => Some(Self::Down),
+            Action::MoveUp => Some(Self::Up),
+            Action::Top => Some(Self::Top),
+            Action::Bottom => Some(Self::Bottom),
+            Action::HalfPageDown => Some(Self::HalfPageDown),
+            Action::HalfPageUp => Some(Self::HalfPageUp),
+            Action::PageDown => Some(Self::PageDown),
+            Action::PageUp => Some(Self::PageUp),
+            Action::ScreenTop => Some(Self::ScreenTop),
+            Action::ScreenMiddle => Some(Self::ScreenMiddle),
+            Action::ScreenBottom => Some(Self::ScreenBottom),
+            _ => None,
+        }
+    }
+
+    // Page motions move the rows along with the cursor, by a count of
+    // pages or, for half pages, by a count of lines like vim.
+    pub fn get_scroll(&self, count: Option<usize>, height: usize) -> isize {
+        let half_page = count.unwrap_or(height / 2).max(1) as isize;
+        let pages = (height * count.unwrap_or(1)) as isize;
+        match self {
+            Self::HalfPageDown => half_page,
+            Self::HalfPageUp => -half_page,
+            Self::PageDown => pages,
+            Self::PageUp => -pages,
+            _ => 0,
+        }
+    }
+
+    // The line the cursor moves to. Lines are the ones the cursor can be on,
+    // a count given to gg and G is the number of a line among them.
+    pub fn get_target(
+        &self,
+        lines: &[usize],
+        current: usize,
+        count: Option<usize>,
+        viewport: Viewport,
+    ) -> Option<usize> {
+        let last = lines.len().checked_sub(1)?;
+        let position = lines
+            .partition_point(|line| *line <= current)
+            .saturating_sub(1);
+        let screen = |line: Option<usize>| {
+            line.map(|line| lines.partition_point(|visible| *visible < line).min(last))
+        };
+        let screen_top = screen(viewport.first).unwrap_or(position);
+        let screen_bottom = screen(viewport.last).unwrap_or(position).max(screen_top);
+        let steps = count.unwrap_or(1).saturating_sub(1);
+
+        let target = match self {
+            Self::Down => position.saturating_add(count.unwrap_or(1)),
+            Self::Up => position.saturating_sub(count.unwrap_or(1)),
+            Self::Top | Self::Bottom if count.is_some() => steps,
+            Self::Top => 0,
+            Self::Bottom => last,
+            Self::HalfPageDown | Self::PageDown => position.saturating_add(
+                self.get_scroll(count, viewport.height.max(1))
+                    .unsigned_abs(),
+            ),
+            Self::HalfPageUp | Self::PageUp => position.saturating_sub(
+                self.get_scroll(count, viewport.height.max(1))
+                    .unsigned_abs(),
+            ),
+            Self::ScreenTop => (screen_top + steps).min(screen_bottom),
+            Self::ScreenMiddle => screen_top + (screen_bottom - screen_top) / 2,
+            Self::ScreenBottom => screen_bottom.saturating_sub(steps).max(screen_top),
+        };
+        lines.get(target.min(last)).copied()
+    }
+}
+
+// What the logs showed before a digit switched the priority, put back when
+// the digit turns out to start a count.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShownPriority {
+    pub priority: Option<u8>,
+    pub line: usize,
+    pub visual_anchor: Option<usize>,
+}
+
+// The keys typed before an action: a count, the start of a sequence like g
+// of gg, or m and ' waiting for the letter of a mark.
+#[derive(Debug, Clone, Default)]
+pub struct PendingKeys {
+    pub count: Option<usize>,
+    pub chords: Vec<KeyChord>,
+    pub mark: Option<Action>,
+    pub switched_from: Option<ShownPriority>,
+    since: Option<Instant>,
+}
+
+impl PendingKeys {
+    pub fn is_empty(&self) -> bool {
+        self.count.is_none() && self.chords.is_empty() && self.mark.is_none()
+    }
+
+    pub fn push_digit(&mut self, digit: u32) {
+        self.count = Some(
+            self.count
+                .unwrap_or(0)
+                .saturating_mul(10)
+                .saturating_add(digit as usize),
+        );
+        self.touch();
+    }
+
+    pub fn push_chord(&mut self, chord: KeyChord) {
+        self.chords.push(chord);
+        self.touch();
+    }
+
+    pub fn set_mark(&mut self, action: Action) {
+        self.mark = Some(action);
+        self.touch();
+    }
+
+    fn touch(&mut self) {
+        self.since = Some(Instant::now());
+    }
+
+    // a mark waits for its letter however long it takes
+    pub fn is_expired(&self) -> bool {
+        self.mark.is_none()
+            && self
+                .since
+                .is_some_and(|since| since.elapsed() >= PENDING_KEYS_TIMEOUT)
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn label(&self) -> String {
+        let mut label = self
+            .count
+            .map(|count| count.to_string())
+            .unwrap_or_default();
+        label.push_str(
+            &self
+                .chords
+                .iter()
+                .map(|chord| chord.to_string())
+                .collect::<String>(),
+        );
+        label
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn viewport(first: usize, last: usize) -> Viewport {
+        Viewport {
+            height: last - first + 1,
+            first: Some(first),
+            last: Some(last),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn counts_stop_at_the_ends() {
+        let lines: Vec<usize> = (0..10).collect();
+        let view = viewport(0, 9);
+
+        assert_eq!(Motion::Down.get_target(&lines, 2, Some(100), view), Some(9));
+        assert_eq!(Motion::Up.get_target(&lines, 2, Some(100), view), Some(0));
+        assert_eq!(
+            Motion::PageDown.get_target(&lines, 2, Some(5), view),
+            Some(9)
+        );
+        assert_eq!(Motion::Top.get_target(&lines, 2, Some(100), view), Some(9));
+    }
+
+    #[test]
+    fn empty_lines_have_no_target() {
+        for motion in [
+            Motion::Down,
+            Motion::Top,
+            Motion::Bottom,
+            Motion::ScreenMiddle,
+        ] {
+            assert_eq!(motion.get_target(&[], 0, None, viewport(0, 9)), None);
+        }
+    }
+
+    #[test]
+    fn line_counts_of_gg_and_g() {
+        // folded invocations leave gaps in the lines
+        let lines = [0, 2, 4, 6, 8];
+        let view = viewport(0, 8);
+
+        assert_eq!(Motion::Top.get_target(&lines, 6, None, view), Some(0));
+        assert_eq!(Motion::Bottom.get_target(&lines, 0, None, view), Some(8));
+        assert_eq!(Motion::Top.get_target(&lines, 6, Some(3), view), Some(4));
+        assert_eq!(Motion::Bottom.get_target(&lines, 0, Some(2), view), Some(2));
+    }
+
+    #[test]
+    fn screen_motions() {
+        let lines: Vec<usize> = (0..20).collect();
+        let view = viewport(4, 12);
+
+        assert_eq!(Motion::ScreenTop.get_target(&lines, 9, None, view), Some(4));
+        assert_eq!(
+            Motion::ScreenMiddle.get_target(&lines, 9, None, view),
+            Some(8)
+        );
+        assert_eq!(
+            Motion::ScreenBottom.get_target(&lines, 9, None, view),
+            Some(12)
+        );
+        assert_eq!(
+            Motion::ScreenTop.get_target(&lines, 9, Some(3), view),
+            Some(6)
+        );
+        assert_eq!(
+            Motion::ScreenBottom.get_target(&lines, 9, Some(100), view),
+            Some(4)
+        );
+        // nothing drawn yet, the cursor stays
+        let undrawn = Viewport::default();
+        assert_eq!(
+            Motion::ScreenMiddle.get_target(&lines, 9, None, undrawn),
+            Some(9)
+        );
+    }
+
+    #[test]
+    fn viewport_outside_the_lines_is_clamped() {
+        let lines: Vec<usize> = (10..20).collect();
+        let view = viewport(2, 50);
+
+        assert_eq!(
+            Motion::ScreenTop.get_target(&lines, 15, None, view),
+            Some(10)
+        );
+        assert_eq!(
+            Motion::ScreenBottom.get_target(&lines, 15, None, view),
+            Some(19)
+        );
+        assert_eq!(
+            Motion::ScreenMiddle.get_target(&lines, 15, None, view),
+            Some(14)
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use crate::ui::ui::View;
+use ratatui::layout::{Position, Rect};
+use std::time::{Duration, Instant};
+
+pub const DOUBLE_CLICK_TIMEOUT: Duration = Duration::from_millis(400);
+pub const WHEEL_LINES: usize = 3;
+
+// The rows of a list on screen and the line each of them shows, none for
+// separators and empty rows. Wrapped entries take several rows.
+#[derive(Debug, Clone, Default)]
+pub struct ClickArea {
+    pub area: Rect,
+    pub lines: Vec<Option<usize>>,
+}
+
+impl ClickArea {
+    pub fn new(area: Rect, lines: Vec<Option<usize>>) -> Self {
+        Self { area, lines }
+    }
+
+    pub fn contains(&self, column: u16, row: u16) -> bool {
+        self.area.contains(Position::new(column, row))
+    }
+
+    pub fn get_line(&self, column: u16, row: u16) -> Option<usize> {
+        if !self.contains(column, row) {
+            return None;
+        }
+        self.lines
+            .get((row - self.area.y) as usize)
+            .copied()
+            .flatten()
+    }
+}
+
+// What was drawn where, written while drawing like the viewports. Panes
+// that are not on screen are left empty.
+#[derive(Debug, Clone, Default)]
+pub struct MouseTargets {
+    pub services: Option<ClickArea>,
+    pub dashboard: Option<ClickArea>,
+    pub logs: Option<ClickArea>,
+    pub tabs: Vec<(Rect, View)>,
+}
+
+impl MouseTargets {
+    pub fn get_tab(&self, column: u16, row: u16) -> Option<View> {
+        self.tabs
+            .iter()
+            .find(|(area, _)| area.contains(Position::new(column, row)))
+            .map(|(_, view)| view.clone())
+    }
+}
+
+// A second click on the same cell soon after the first one opens what it is on
+#[derive(Debug, Clone, Copy)]
+pub struct Click {
+    column: u16,
+    row: u16,
+    at: Instant,
+}
+
+impl Click {
+    pub fn new(column: u16, row: u16) -> Self {
+        Self {
+            column,
+            row,
+            at: Instant::now(),
+        }
+    }
+
+    pub fn is_double(&self, previous: Option<Click>) -> bool {
+        previous.is_some_and(|previous| {
+            previous.column == self.column
+                && previous.row == self.row
+                && self.at.duration_since(previous.at) < DOUBLE_CLICK_TIMEOUT
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_rows_to_lines() {
+        // a separator row and an entry wrapped over two rows
+        let area = ClickArea::new(
+            Rect::new(2, 5, 20, 4),
+            vec![None, Some(7), Some(8), Some(8)],
+        );
+
+        assert_eq!(area.get_line(2, 5), None);
+        assert_eq!(area.get_line(10, 6), Some(7));
+        assert_eq!(area.get_line(21, 8), Some(8));
+    }
+
+    #[test]
+    fn ignores_clicks_outside_or_below_the_rows() {
+        let area = ClickArea::new(Rect::new(2, 5, 20, 4), vec![Some(0), Some(1)]);
+
+        assert_eq!(area.get_line(1, 5), None);
+        assert_eq!(area.get_line(22, 5), None);
+        assert_eq!(area.get_line(10, 4), None);
+        assert_eq!(area.get_line(10, 9), None);
+        // inside the area but past the last row drawn
+        assert_eq!(area.get_line(10, 7), None);
+    }
+
+    #[test]
+    fn double_clicks_need_the_same_cell() {
+        let first = Click::new(3, 4);
+
+        assert!(Click::new(3, 4).is_double(Some(first)));
+        assert!(!Click::new(3, 5).is_double(Some(first)));
+        assert!(!Click::new(3, 4).is_double(None));
+    }
+
+    #[test]
+    fn finds_the_tab_under_the_mouse() {
+        let targets = MouseTargets {
+            tabs: vec![
+                (Rect::new(0, 0, 10, 1), View::Dashboard),
+                (Rect::new(10, 0, 10, 1), View::ServiceUnits),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(targets.get_tab(12, 0), Some(View::ServiceUnits));
+        assert_eq!(targets.get_tab(12, 1), None);
+    }
+}
@@ -0,0 +1,218 @@
+use crate::core::journal::JournalLog;
+use std::collections::HashMap;
+
+const TRIM_CHARS: &[char] = &[
+    ',', ';', ':', '.', '(', ')', '[', ']', '{', '}', '"', '\'', '<', '>',
+];
+
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    pub template: String,
+    pub count: usize,
+    pub first_seen: u64,
+    pub last_seen: u64,
+    // positions of the entries in the list the patterns were built from
+    pub lines: Vec<usize>,
+}
+
+#[derive(Debug)]
+pub struct PatternView {
+    pub patterns: Vec<Pattern>,
+    pub current_line: usize,
+    // the pattern whose entries are listed and the cursor among them
+    pub drill: Option<(usize, usize)>,
+}
+
+impl PatternView {
+    pub fn new(entries: &[JournalLog]) -> Self {
+        Self {
+            patterns: build_patterns(entries),
+            current_line: 0,
+            drill: None,
+        }
+    }
+
+    pub fn drill_in(&mut self) {
+        if self.patterns.get(self.current_line).is_some() {
+            self.drill = Some((self.current_line, 0));
+        }
+    }
+
+    pub fn drill_out(&mut self) {
+        self.drill = None;
+    }
+
+    // the log line under the cursor when drilled into a pattern
+    pub fn get_selected_line(&self) -> Option<usize> {
+        let (pattern, line) = self.drill?;
+        self.patterns.get(pattern)?.lines.get(line).copied()
+    }
+
+    pub fn move_cursor_down(&mut self) {
+        match &mut self.drill {
+            Some((pattern, line)) => {
+                let len = self.patterns[*pattern].lines.len();
+                if *line < len.saturating_sub(1) {
+                    *line += 1;
+                }
+            }
+            None => {
+                if self.current_line < self.patterns.len().saturating_sub(1) {
+                    self.current_line += 1;
+                }
+            }
+        }
+    }
+
+    pub fn move_cursor_up(&mut self) {
+        match &mut self.drill {
+            Some((_, line)) => *line = line.saturating_sub(1),
+            None => self.current_line = self.current_line.saturating_sub(1),
+        }
+    }
+}
+
+fn is_uuid(token: &str) -> bool {
+    let parts: Vec<&str> = token.split('-').collect();
+    parts.len() == 5
+        && parts
+            .iter()
+            .zip([8, 4, 4, 4, 12])
+            .all(|(part, len)| part.len() == len && part.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+// an IPv4 address with an optional port
+fn is_ip(token: &str) -> bool {
+    let address = token.split(':').next().unwrap_or(token);
+    let octets: Vec<&str> = address.split('.').collect();
+    octets.len() == 4
+        && octets
+            .iter()
+            .all(|octet| !octet.is_empty() && octet.parse::<u8>().is_ok())
+}
+
+// 0x prefixed, or long enough not to be a word, like ids and hashes
+fn is_hex(token: &str) -> bool {
+    if let Some(hex) = token.strip_prefix("0x") {
+        return !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit());
+    }
+    token.len() >= 8
+        && token.chars().all(|c| c.is_ascii_hexdigit())
+        && token.chars().any(|c| c.is_ascii_digit())
+}
+
+// numbers, also with separators or a short unit like 12ms or 100%
+fn is_number(token: &str) -> bool {
+    let digits_end = token
+        .find(|c: char| !(c.is_ascii_digit() || "+-.,:/".contains(c)))
+        .unwrap_or(token.len());
+    let (number, unit) = token.split_at(digits_end);
+
+    number.chars().any(|c| c.is_ascii_digit())
+        && unit.len() <= 3
+        && unit.chars().all(|c| c.is_ascii_alphabetic() || c == '%')
+}
+
+fn mask_token(token: &str) -> String {
+    // key=value keeps the key
+    if let Some((key, value)) = token.split_once('=') {
+        return format!("{}={}", key, mask_token(value));
+    }
+
+    let core = token.trim_matches(TRIM_CHARS);
+    if core.is_empty() {
+        return token.to_string();
+    }
+
+    let mask = if is_uuid(core) {
+        "<UUID>"
+    } else if is_ip(core) {
+        "<IP>"
+    } else if is_number(core) {
+        "<NUM>"
+    } else if is_hex(core) {
+        "<HEX>"
+    } else {
+        return token.to_string();
+    };
+
+    token.replacen(core, mask, 1)
+}
+
+pub fn mask_message(message: &str) -> String {
+    message
+        .split_whitespace()
+        .map(mask_token)
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+// most frequent templates first
+pub fn build_patterns(entries: &[JournalLog]) -> Vec<Pattern> {
+    let mut patterns: Vec<Pattern> = vec![];
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for (line, log) in entries.iter().enumerate() {
+        let template = mask_message(&log.log_message);
+
+        match index.get(&template) {
+            Some(idx) => {
+                let pattern = &mut patterns[*idx];
+                pattern.count += 1;
+                pattern.first_seen = pattern.first_seen.min(log.realtime);
+                pattern.last_seen = pattern.last_seen.max(log.realtime);
+                pattern.lines.push(line);
+            }
+            None => {
+                index.insert(template.clone(), patterns.len());
+                patterns.push(Pattern {
+                    template,
+                    count: 1,
+                    first_seen: log.realtime,
+                    last_seen: log.realtime,
+                    lines: vec![line],
+                });
+            }
+        }
+    }
+
+    patterns.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| b.last_seen.cmp(&a.last_seen))
+    });
+    patterns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_variable_tokens() {
+        assert_eq!(
+            mask_message("connection from 10.0.0.1:443 took 12ms"),
+            "connection from <IP> took <NUM>"
+        );
+        assert_eq!(
+            mask_message("session 3f2a9c1b-0d4e-4a5b-9c8d-112233445566 opened"),
+            "session <UUID> opened"
+        );
+        assert_eq!(mask_message("id deadbeef01 at 0x1f"), "id <HEX> at <HEX>");
+    }
+
+    #[test]
+    fn keeps_keys_and_punctuation() {
+        assert_eq!(mask_message("pid=1234 exited"), "pid=<NUM> exited");
+        assert_eq!(mask_message("(code 3)."), "(code <NUM>).");
+    }
+
+    #[test]
+    fn leaves_words_alone() {
+        assert_eq!(
+            mask_message("Started nginx  server"),
+            "Started nginx server"
+        );
+        assert_eq!(mask_message("deadbeef"), "deadbeef");
+    }
+}
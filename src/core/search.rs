@@ -0,0 +1,84 @@
+use crate::core::journal::JournalLog;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone)]
+pub enum SearchRow {
+    Group {
+        unit: String,
+        priority: u8,
+        count: usize,
+    },
+    Entry(JournalLog),
+}
+
+#[derive(Debug)]
+pub struct GlobalSearch {
+    pub query: String,
+    pub since: String,
+    pub rows: Vec<SearchRow>,
+    pub matches: usize,
+    pub error: Option<String>,
+    pub current_line: usize,
+}
+
+impl GlobalSearch {
+    pub fn new(query: &str, since: &str) -> Self {
+        Self {
+            query: query.to_string(),
+            since: since.to_string(),
+            rows: vec![],
+            matches: 0,
+            error: None,
+            current_line: 0,
+        }
+    }
+
+    // results are grouped by unit and then by the entry's own priority,
+    // entries without a unit can not be opened and are left out
+    pub fn set_results(&mut self, logs: Vec<JournalLog>) {
+        let mut groups: BTreeMap<(String, u8), Vec<JournalLog>> = BTreeMap::new();
+
+        for log in logs.into_iter().filter(|log| !log.unit.is_empty()) {
+            groups
+                .entry((log.unit.clone(), log.priority.clamp(1, 7)))
+                .or_default()
+                .push(log);
+        }
+
+        self.matches = groups.values().map(|entries| entries.len()).sum();
+        self.rows = groups
+            .into_iter()
+            .flat_map(|((unit, priority), entries)| {
+                let group = SearchRow::Group {
+                    unit,
+                    priority,
+                    count: entries.len(),
+                };
+                std::iter::once(group).chain(entries.into_iter().map(SearchRow::Entry))
+            })
+            .collect();
+        self.current_line = 0;
+    }
+
+    pub fn set_error(&mut self, error: String) {
+        self.rows.clear();
+        self.matches = 0;
+        self.error = Some(error);
+    }
+
+    pub fn get_current_row(&self) -> Option<&SearchRow> {
+        self.rows.get(self.current_line)
+    }
+
+    pub fn move_cursor_down(&mut self) {
+        if self.current_line < self.rows.len().saturating_sub(1) {
+            self.current_line += 1;
+        }
+    }
+
+    pub fn move_cursor_up(&mut self) {
+        if self.current_line > 0 {
+            self.current_line -= 1;
+        }
+    }
+}
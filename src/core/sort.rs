@@ -0,0 +1,177 @@
+use crate::core::system::{ServiceUnitFiles, ServiceUnits};
+use std::cmp::Ordering;
+
+// the lists that can be sorted, each keeps its own UnitSort
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortList {
+    Units,
+    Files,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortColumn {
+    Name,
+    Load,
+    Active,
+    Sub,
+    State,
+    Preset,
+}
+
+impl SortColumn {
+    pub fn columns_for(list: SortList) -> &'static [SortColumn] {
+        match list {
+            SortList::Units => &[
+                SortColumn::Name,
+                SortColumn::Load,
+                SortColumn::Active,
+                SortColumn::Sub,
+            ],
+            SortList::Files => &[SortColumn::Name, SortColumn::State, SortColumn::Preset],
+        }
+    }
+}
+
+// column is None until the user sorts, units are then in systemctl's order
+#[derive(Debug, Clone)]
+pub struct UnitSort {
+    pub column: Option<SortColumn>,
+    pub ascending: bool,
+}
+
+impl UnitSort {
+    pub fn new() -> Self {
+        Self {
+            column: None,
+            ascending: true,
+        }
+    }
+
+    pub fn cycle_column(&mut self, list: SortList) {
+        let columns = SortColumn::columns_for(list);
+
+        let next = match self.column {
+            Some(column) => columns
+                .iter()
+                .position(|c| *c == column)
+                .map(|idx| (idx + 1) % columns.len())
+                .unwrap_or(0),
+            None => 0,
+        };
+
+        self.column = columns.get(next).copied();
+        self.ascending = true;
+    }
+
+    pub fn toggle_direction(&mut self) {
+        if self.column.is_some() {
+            self.ascending = !self.ascending;
+        }
+    }
+
+    fn directed(&self, ordering: Ordering) -> Ordering {
+        if self.ascending {
+            ordering
+        } else {
+            ordering.reverse()
+        }
+    }
+
+    // states are ordered by their declaration order, running and enabled
+    // first, and ties are broken by name
+    pub fn sort_units(&self, units: &mut [ServiceUnits]) {
+        let Some(column) = self.column else {
+            return;
+        };
+
+        units.sort_by(|a, b| {
+            let ordering = match column {
+                SortColumn::Load => a.load.cmp(&b.load),
+                SortColumn::Active => a.active.cmp(&b.active),
+                SortColumn::Sub => a.sub.cmp(&b.sub),
+                _ => Ordering::Equal,
+            };
+            self.directed(ordering.then_with(|| a.name.cmp(&b.name)))
+        });
+    }
+
+    pub fn sort_unit_files(&self, files: &mut [ServiceUnitFiles]) {
+        let Some(column) = self.column else {
+            return;
+        };
+
+        files.sort_by(|a, b| {
+            let ordering = match column {
+                SortColumn::State => a.state.cmp(&b.state),
+                SortColumn::Preset => a.preset.cmp(&b.preset),
+                _ => Ordering::Equal,
+            };
+            self.directed(ordering.then_with(|| a.name.cmp(&b.name)))
+        });
+    }
+}
+
+impl Default for UnitSort {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::system::{Active, Load, Sub};
+
+    fn unit(name: &str, load: Load, active: Active, sub: Sub) -> ServiceUnits {
+        ServiceUnits {
+            name: name.to_string(),
+            load,
+            active,
+            sub,
+            description: "".to_string(),
+        }
+    }
+
+    fn sorted(sort: &UnitSort) -> Vec<String> {
+        let mut units = vec![
+            unit("b", Load::Loaded, Active::Active, Sub::Running),
+            unit("a", Load::Loaded, Active::InActive, Sub::Dead),
+            unit("c", Load::NotFound, Active::InActive, Sub::Failed),
+            unit("d", Load::Loaded, Active::Active, Sub::Exited),
+        ];
+        sort.sort_units(&mut units);
+        units.into_iter().map(|unit| unit.name).collect()
+    }
+
+    #[test]
+    fn cycles_columns_and_directions() {
+        let mut sort = UnitSort::new();
+        // unsorted keeps systemctl's order, there is no direction to reverse
+        sort.toggle_direction();
+        assert!(sort.ascending);
+        assert_eq!(sorted(&sort), vec!["b", "a", "c", "d"]);
+
+        sort.cycle_column(SortList::Units);
+        assert_eq!(sort.column, Some(SortColumn::Name));
+        assert_eq!(sorted(&sort), vec!["a", "b", "c", "d"]);
+        sort.toggle_direction();
+        assert_eq!(sorted(&sort), vec!["d", "c", "b", "a"]);
+
+        // the next column starts ascending again, ties go by name
+        sort.cycle_column(SortList::Units);
+        assert_eq!(sort.column, Some(SortColumn::Load));
+        assert_eq!(sorted(&sort), vec!["a", "b", "d", "c"]);
+
+        sort.cycle_column(SortList::Units);
+        assert_eq!(sorted(&sort), vec!["b", "d", "a", "c"]);
+        sort.toggle_direction();
+        assert_eq!(sorted(&sort), vec!["c", "a", "d", "b"]);
+
+        sort.cycle_column(SortList::Units);
+        assert_eq!(sort.column, Some(SortColumn::Sub));
+        assert_eq!(sorted(&sort), vec!["b", "d", "a", "c"]);
+
+        sort.cycle_column(SortList::Units);
+        assert_eq!(sort.column, Some(SortColumn::Name));
+    }
+}
@@ -2,7 +2,7 @@ use crate::core::error::{Result, RounalError};
 use log::info;
 use tokio::process::Command;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum State {
     Enabled,
     Disabled,
@@ -33,7 +33,7 @@ impl State {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Preset {
     Enabled,
     Disabled,
@@ -52,7 +52,7 @@ impl Preset {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Load {
     Loaded,
     NotFound,
@@ -60,7 +60,7 @@ pub enum Load {
 }
 
 impl Load {
-    fn get_load_state(state_as_str: &str) -> Self {
+    pub(crate) fn get_load_state(state_as_str: &str) -> Self {
         match state_as_str {
             "loaded" => Self::Loaded,
             "not-found" => Self::NotFound,
@@ -69,7 +69,7 @@ impl Load {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Active {
     Active,
     InActive,
@@ -77,7 +77,7 @@ pub enum Active {
 }
 
 impl Active {
-    fn get_active_state(state_as_str: &str) -> Self {
+    pub(crate) fn get_active_state(state_as_str: &str) -> Self {
         match state_as_str {
             "active" => Self::Active,
             "inactive" => Self::InActive,
@@ -86,7 +86,7 @@ impl Active {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Sub {
     Running,
     Exited,
@@ -101,7 +101,7 @@ pub enum Sub {
 }
 
 impl Sub {
-    fn get_sub_state(state_as_str: &str) -> Self {
+    pub(crate) fn get_sub_state(state_as_str: &str) -> Self {
         match state_as_str {
             "running" => Self::Running,
             "exited" => Self::Exited,
@@ -153,10 +153,9 @@ pub async fn get_list_units() -> Result<Vec<ServiceUnits>> {
         .await?;
 
     if !out.status.success() {
-        return Err(RounalError::SystemCtlError(format!(
-            "{}",
-            String::from_utf8_lossy(&out.stderr).to_string()
-        )));
+        return Err(RounalError::SystemCtlError(
+            String::from_utf8_lossy(&out.stderr).to_string(),
+        ));
     }
 
     let stdout = String::from_utf8_lossy(&out.stdout);
@@ -164,7 +163,7 @@ pub async fn get_list_units() -> Result<Vec<ServiceUnits>> {
     let services: Vec<ServiceUnits> = stdout
         .lines()
         .skip(1) // first is column headers
-        .filter_map(|line| parse_service_units(line))
+        .filter_map(parse_service_units)
         .collect();
 
     Ok(services)
@@ -208,6 +207,52 @@ fn parse_service_units(service_line: &str) -> Option<ServiceUnits> {
     })
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnitAction {
+    Start,
+    Stop,
+    Restart,
+}
+
+impl UnitAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::Stop => "stop",
+            Self::Restart => "restart",
+        }
+    }
+
+    pub fn past_tense(&self) -> &'static str {
+        match self {
+            Self::Start => "Started",
+            Self::Stop => "Stopped",
+            Self::Restart => "Restarted",
+        }
+    }
+}
+
+pub async fn run_unit_action(action: UnitAction, unit: &str) -> Result<()> {
+    info!("{} {}", action.name(), unit);
+
+    let out = Command::new("sudo")
+        .arg("systemctl")
+        .arg(action.name())
+        .arg(unit)
+        .output()
+        .await?;
+
+    if !out.status.success() {
+        return Err(RounalError::SystemCtlError(format!(
+            "{} {}: {}",
+            action.name(),
+            unit,
+            String::from_utf8_lossy(&out.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
 pub async fn get_list_unit_files() -> Result<Vec<ServiceUnitFiles>> {
     let out = Command::new("systemctl")
         .arg("list-unit-files")
@@ -217,10 +262,9 @@ pub async fn get_list_unit_files() -> Result<Vec<ServiceUnitFiles>> {
         .await?;
 
     if !out.status.success() {
-        return Err(RounalError::SystemCtlError(format!(
-            "{}",
-            String::from_utf8_lossy(&out.stderr)
-        )));
+        return Err(RounalError::SystemCtlError(
+            String::from_utf8_lossy(&out.stderr).to_string(),
+        ));
     }
 
     let stdout = String::from_utf8_lossy(&out.stdout);
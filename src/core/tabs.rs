@@ -0,0 +1,88 @@
+use crate::core::{
+    error::Result,
+    journal::{get_journal_logs, SharedJournalLogs, TimeWindow},
+    matches::JournalMatch,
+};
+use log::{error, info};
+use tokio::task::JoinHandle;
+
+// Everything a tab needs to be restored. The focused tab is kept in App and
+// UI as before and written back here when another tab is focused.
+#[derive(Debug)]
+pub struct LogTab {
+    pub service: String,
+    pub units: Vec<String>,
+    pub matches: Vec<JournalMatch>,
+    pub logs: Option<SharedJournalLogs>,
+    pub loading: Option<JoinHandle<Result<SharedJournalLogs>>>,
+    pub priority: u8,
+    pub search_query: String,
+    pub current_line: usize,
+    pub h_scroll: usize,
+}
+
+impl LogTab {
+    pub fn new(service: &str, priority: u8) -> Self {
+        Self {
+            service: service.to_string(),
+            units: vec![service.to_string()],
+            matches: vec![],
+            logs: None,
+            loading: None,
+            priority,
+            search_query: "".to_string(),
+            current_line: 0,
+            h_scroll: 0,
+        }
+    }
+
+    // the tab of a merged timeline is named after all of its units
+    pub fn merged(units: &[String], priority: u8) -> Self {
+        Self {
+            units: units.to_vec(),
+            ..Self::new(&units.join(" + "), priority)
+        }
+    }
+
+    pub fn load_in_background(&mut self, window: &TimeWindow) {
+        let units = self.units.clone();
+        let matches = self.matches.clone();
+        let window = window.clone();
+        info!("loading {} in background", self.service);
+        self.loading = Some(tokio::spawn(async move {
+            get_journal_logs(&units, &matches, &window).await
+        }));
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.loading.is_some()
+    }
+
+    pub fn cancel_loading(&mut self) {
+        if let Some(handle) = self.loading.take() {
+            handle.abort();
+        }
+    }
+
+    // returns true when the logs arrived during this call
+    pub async fn poll_loading(&mut self) -> bool {
+        if !self
+            .loading
+            .as_ref()
+            .is_some_and(|handle| handle.is_finished())
+        {
+            return false;
+        }
+
+        let Some(handle) = self.loading.take() else {
+            return false;
+        };
+
+        match handle.await {
+            Ok(Ok(logs)) => self.logs = Some(logs),
+            Ok(Err(e)) => error!("Loading logs for {} failed: {}", self.service, e),
+            Err(e) => error!("Loading task for {} failed: {}", self.service, e),
+        }
+        true
+    }
+}
@@ -1,3 +1,5 @@
 pub mod layouts;
 pub mod styles;
+pub mod table;
+#[allow(clippy::module_inception)]
 pub mod ui;
@@ -1,23 +1,35 @@
 use super::ui::UI;
 use crate::{
     core::{
+        bookmarks::Bookmark,
         config::Config,
-        journal::JournalLog,
+        filter::UnitFilter,
+        fuzzy::FuzzyCandidate,
+        journal::{format_timestamp, JournalLog},
+        jump::JUMP_EXAMPLES,
+        keymap::Action,
+        patterns::Pattern,
+        sort::UnitSort,
         system::{ServiceUnitFiles, ServiceUnits},
+        tabs::LogTab,
     },
+    ui::table::{scroll, truncate, wrap, TableColumn},
     ui::ui::View,
     util::{
         get_active_color_str, get_load_color_str, get_preset_color_str, get_state_color_str,
-        get_sub_color_str, PadStr,
+        get_sub_color_str, map_to_priority_str,
     },
 };
 use ratatui::{
     layout::Alignment,
     style::{Modifier, Style},
     text::{Line, Span, Text},
-    widgets::{ListItem, Paragraph},
+    widgets::{Cell, ListItem, Paragraph, Row},
 };
 
+const MARK_SYMBOL: &str = "+ ";
+const MERGED_UNIT_COLORS: [&str; 5] = ["blue", "yellow", "green", "white", "red"];
+
 #[derive(Debug)]
 pub struct Styler {
     pub config: Config,
@@ -31,65 +43,247 @@ impl Styler {
     }
 
     pub(crate) fn get_bottom_info(&self, ui: &UI) -> Paragraph<'static> {
-        if ui.is_in_search_mode {
+        if ui.is_in_global_search_mode {
+            let (query_style, since_style) = if ui.is_editing_search_since {
+                (Modifier::empty(), Modifier::UNDERLINED)
+            } else {
+                (Modifier::UNDERLINED, Modifier::empty())
+            };
+
+            Paragraph::new(Line::from(vec![
+                Span::raw(" -- SEARCH ALL SERVICES: "),
+                Span::styled(
+                    ui.global_search_query.clone(),
+                    Style::default().add_modifier(query_style),
+                ),
+                Span::raw("  since: "),
+                Span::styled(
+                    ui.global_search_since.clone(),
+                    Style::default().add_modifier(since_style),
+                ),
+                Span::raw("  [Tab] switch field"),
+            ]))
+            .alignment(Alignment::Left)
+            .style(Style::default().fg(self.config.get_palette_color("blue")))
+        } else if ui.is_in_search_mode {
             Paragraph::new(format!(" -- SEARCH MODE: {}", ui.search_query))
                 .alignment(Alignment::Left)
                 .style(Style::default().fg(self.config.get_palette_color("blue")))
-        } else {
-            Paragraph::new(" -- Press [?] for help -- ")
-                .alignment(Alignment::Center)
-                .style(Style::default().fg(self.config.get_palette_color("white")))
-        }
-    }
-
-    pub(crate) fn get_column_titles(&self, view: &View) -> ListItem<'static> {
-        match view {
-            View::ServiceUnits => ListItem::new(Line::from(vec![
+        } else if ui.is_in_export_prompt {
+            Paragraph::new(Line::from(vec![
+                Span::raw(" -- EXPORT TO: "),
                 Span::styled(
-                    " NAME".pad_with(20),
-                    self.config.get_palette_color("yellow"),
+                    ui.export_path.clone(),
+                    Style::default().add_modifier(Modifier::UNDERLINED),
                 ),
-                Span::styled("LOAD".pad_with(10), self.config.get_palette_color("yellow")),
+                Span::raw("  [Tab] format  [Enter] write  [Esc] cancel"),
+            ]))
+            .alignment(Alignment::Left)
+            .style(Style::default().fg(self.config.get_palette_color("blue")))
+        } else if ui.is_in_bundle_prompt {
+            Paragraph::new(Line::from(vec![
+                Span::raw(" -- DIAGNOSTIC BUNDLE, LOGS SINCE: "),
                 Span::styled(
-                    "ACTIVE".pad_with(10),
-                    self.config.get_palette_color("yellow"),
+                    ui.bundle_since.clone(),
+                    Style::default().add_modifier(Modifier::UNDERLINED),
                 ),
-                Span::styled("SUB".pad_with(10), self.config.get_palette_color("yellow")),
-                Span::styled("MESSAGE", self.config.get_palette_color("yellow")),
-            ])),
-            View::ServiceUnitFiles => ListItem::new(Line::from(vec![
+                Span::raw("  [Enter] create  [Esc] cancel"),
+            ]))
+            .alignment(Alignment::Left)
+            .style(Style::default().fg(self.config.get_palette_color("blue")))
+        } else if ui.is_in_jump_prompt {
+            Paragraph::new(Line::from(vec![
+                Span::raw(" -- JUMP TO TIME: "),
                 Span::styled(
-                    " NAME".pad_with(20),
-                    self.config.get_palette_color("yellow"),
+                    ui.jump_time.clone(),
+                    Style::default().add_modifier(Modifier::UNDERLINED),
                 ),
+                Span::raw(format!("  ({})  [Enter] jump  [Esc] cancel", JUMP_EXAMPLES)),
+            ]))
+            .alignment(Alignment::Left)
+            .style(Style::default().fg(self.config.get_palette_color("blue")))
+        } else if ui.is_in_annotation_prompt {
+            Paragraph::new(Line::from(vec![
+                Span::raw(" -- NOTE: "),
                 Span::styled(
-                    "STATE".pad_with(10),
-                    self.config.get_palette_color("yellow"),
+                    ui.annotation.clone(),
+                    Style::default().add_modifier(Modifier::UNDERLINED),
                 ),
-                Span::styled(
-                    "PRESET".pad_with(10),
-                    self.config.get_palette_color("yellow"),
+                Span::raw("  [Enter] save  [Esc] cancel"),
+            ]))
+            .alignment(Alignment::Left)
+            .style(Style::default().fg(self.config.get_palette_color("blue")))
+        } else if ui.is_in_confirm_prompt {
+            Paragraph::new(format!(" -- {}  [y] yes  [n] no", ui.confirm_question))
+                .alignment(Alignment::Left)
+                .style(Style::default().fg(self.config.get_palette_color("red")))
+        } else if ui.is_in_command_mode {
+            let mut spans = vec![Span::raw(format!(" :{}", ui.command_line))];
+            if let Some(completion) = &ui.command_completion {
+                // the list scrolls along so the selected candidate stays in view
+                let skip = completion.selected.saturating_sub(2);
+                spans.push(Span::raw(if skip > 0 { "   < " } else { "   " }));
+                for (idx, candidate) in completion.candidates.iter().enumerate().skip(skip) {
+                    let style = if idx == completion.selected {
+                        Style::default().add_modifier(Modifier::REVERSED)
+                    } else {
+                        Style::default().fg(self.config.get_palette_color("gray"))
+                    };
+                    spans.push(Span::styled(candidate.clone(), style));
+                    spans.push(Span::raw(" "));
+                }
+            }
+
+            Paragraph::new(Line::from(spans))
+                .alignment(Alignment::Left)
+                .style(Style::default().fg(self.config.get_palette_color("blue")))
+        } else if let Some((first, last)) = ui.get_selection() {
+            Paragraph::new(format!(
+                " -- VISUAL LINE: {} entries  [y] yank  [w] export  [Esc / V] cancel",
+                last - first + 1
+            ))
+            .alignment(Alignment::Left)
+            .style(Style::default().fg(self.config.get_palette_color("blue")))
+        } else if !ui.pending_keys.is_empty() {
+            let label = ui.pending_keys.label();
+            Paragraph::new(match ui.pending_keys.mark {
+                Some(_) => format!(" {} -- mark a-z ", label),
+                None => format!(" {} ", label),
+            })
+            .alignment(Alignment::Right)
+            .style(Style::default().fg(self.config.get_palette_color("white")))
+        } else if let Some(notification) = ui
+            .notification
+            .as_ref()
+            .filter(|notification| notification.is_visible())
+        {
+            Paragraph::new(format!(" -- {} -- ", notification.message))
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(self.config.get_palette_color(
+                    if notification.is_error {
+                        "red"
+                    } else {
+                        "green"
+                    },
+                )))
+        } else {
+            Paragraph::new(format!(
+                " -- Press [{}] for help -- ",
+                self.config.keymap.get_keys(Action::Help)
+            ))
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(self.config.get_palette_color("white")))
+        }
+    }
+
+    pub(crate) fn get_column_titles(
+        &self,
+        columns: &[TableColumn],
+        sort: Option<&UnitSort>,
+    ) -> Row<'static> {
+        let mut cells = vec![Cell::from("")];
+
+        cells.extend(columns.iter().map(|column| {
+            let is_sorted = sort.is_some_and(|sort| {
+                column.sort_column().is_some() && column.sort_column() == sort.column
+            });
+
+            let title = match sort {
+                Some(sort) if is_sorted => format!(
+                    "{} {}",
+                    column.title(),
+                    if sort.ascending { "▲" } else { "▼" }
                 ),
-            ])),
+                _ => column.title().to_string(),
+            };
+
+            Cell::from(title)
+        }));
+
+        Row::new(cells).style(
+            Style::default()
+                .fg(self.config.get_palette_color("yellow"))
+                .add_modifier(Modifier::BOLD),
+        )
+    }
+
+    pub(crate) fn get_pane_border_style(&self, is_focused: bool) -> Style {
+        if is_focused {
+            Style::default()
+                .fg(self.config.get_palette_color("blue"))
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(self.config.get_palette_color("gray"))
         }
     }
 
-    pub(crate) fn get_services_container(&self, view: View) -> Line<'static> {
+    pub(crate) fn get_services_container(
+        &self,
+        view: View,
+        filters: &[UnitFilter],
+    ) -> Line<'static> {
         let active = Style::default()
             .fg(self.config.get_palette_color("green"))
             .add_modifier(Modifier::BOLD);
         let inactive = Style::default().fg(self.config.get_palette_color("gray"));
 
-        let styles = match view {
-            View::ServiceUnits => (active, inactive),
-            View::ServiceUnitFiles => (inactive, active),
-        };
+        let style = |tab: View| if tab == view { active } else { inactive };
 
-        Line::from(vec![
-            Span::styled(" Service units ", styles.0),
+        let mut spans = vec![
+            Span::styled(" Dashboard ", style(View::Dashboard)),
             Span::raw(" / "),
-            Span::styled(" Service unit files ", styles.1),
-        ])
+            Span::styled(" Service units ", style(View::ServiceUnits)),
+            Span::raw(" / "),
+            Span::styled(" Service unit files ", style(View::ServiceUnitFiles)),
+        ];
+
+        // filters only apply to units
+        if view == View::ServiceUnits {
+            let chip = Style::default()
+                .fg(self.config.get_palette_color("black"))
+                .bg(self.config.get_palette_color("blue"));
+
+            for filter in filters {
+                spans.push(Span::raw(" "));
+                spans.push(Span::styled(format!(" {} ", filter.label()), chip));
+            }
+        }
+
+        Line::from(spans)
+    }
+
+    pub(crate) fn get_tab_bar(&self, tabs: &[LogTab], active: Option<usize>) -> Line<'static> {
+        let selected = Style::default()
+            .fg(self.config.get_palette_color("black"))
+            .bg(self.config.get_palette_color("blue"))
+            .add_modifier(Modifier::BOLD);
+        let other = Style::default().fg(self.config.get_palette_color("gray"));
+
+        let mut spans = vec![];
+        for (idx, tab) in tabs.iter().enumerate() {
+            let style = if Some(idx) == active { selected } else { other };
+            let loading = if tab.is_loading() { " …" } else { "" };
+            spans.push(Span::styled(
+                format!(" {}:{}{} ", idx + 1, tab.service, loading),
+                style,
+            ));
+            spans.push(Span::raw(" "));
+        }
+
+        Line::from(spans)
+    }
+
+    // bookmarked entries are flagged in the logs and the lists of entries
+    pub(crate) fn get_bookmark_marker(&self, bookmark: Option<&Bookmark>) -> (String, Style) {
+        (
+            bookmark
+                .map(|bookmark| bookmark.get_marker())
+                .unwrap_or(" ")
+                .to_string(),
+            Style::default()
+                .fg(self.config.get_palette_color("yellow"))
+                .add_modifier(Modifier::BOLD),
+        )
     }
 
     pub(crate) fn create_log_list_item(
@@ -97,8 +291,10 @@ impl Styler {
         index: usize,
         current_line: usize,
         log: &JournalLog,
+        bookmark: Option<&Bookmark>,
     ) -> ListItem<'static> {
         let is_on_cursor = index == current_line;
+        let (marker, marker_style) = self.get_bookmark_marker(bookmark);
 
         ListItem::from(Text::from(Line::from(vec![
             Span::styled(
@@ -110,6 +306,7 @@ impl Styler {
                 .to_string(),
                 Style::default().fg(self.config.get_palette_color("blue")),
             ),
+            Span::styled(format!("{} ", marker), marker_style),
             Span::styled(
                 "[".to_string(),
                 Style::default()
@@ -117,7 +314,7 @@ impl Styler {
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(
-                format!("{}", log.timestamp),
+                log.timestamp.clone(),
                 Style::default()
                     .fg(self.config.get_palette_color("white"))
                     .add_modifier(Modifier::BOLD),
@@ -143,7 +340,7 @@ impl Styler {
                 Style::default().fg(self.config.get_palette_color("white")),
             ),
             Span::styled(
-                format!("{}", log.log_message),
+                log.log_message.clone(),
                 Style::default().fg(self.config.get_palette_color("gray")),
             ),
             Span::styled(
@@ -158,15 +355,16 @@ impl Styler {
         ])))
     }
 
-    pub(crate) fn create_files_list_item(
+    pub(crate) fn create_search_group_item(
         &self,
         index: usize,
         current_line: usize,
-        file: &ServiceUnitFiles,
+        unit: &str,
+        priority: u8,
+        count: usize,
     ) -> ListItem<'static> {
         let is_on_cursor = index == current_line;
-        let state_c = get_state_color_str(&file.state);
-        let preset_c = get_preset_color_str(&file.preset);
+        let priority_str = map_to_priority_str(&priority);
 
         ListItem::from(Text::from(Line::from(vec![
             Span::styled(
@@ -179,32 +377,20 @@ impl Styler {
                 Style::default().fg(self.config.get_palette_color("blue")),
             ),
             Span::styled(
-                "[".to_string(),
+                format!("{} ", unit),
                 Style::default()
-                    .fg(self.config.get_palette_color("yellow"))
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                format!("{}", file.name),
-                Style::default()
-                    .fg(self.config.get_palette_color("white"))
+                    .fg(self.config.get_palette_color("green"))
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(
-                "] ".to_string(),
+                format!("{}/{} ", priority, priority_str),
                 Style::default()
-                    .fg(self.config.get_palette_color("yellow"))
+                    .fg(self.config.get_priority_color(priority_str))
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(
-                format!("{:?} ", file.state),
-                Style::default().fg(self.config.get_palette_color(state_c)),
-            ),
-            Span::styled(
-                format!("{:?} ", file.preset),
-                Style::default()
-                    .fg(self.config.get_palette_color(preset_c))
-                    .add_modifier(Modifier::BOLD),
+                format!("({} matches)", count),
+                Style::default().fg(self.config.get_palette_color("yellow")),
             ),
             Span::styled(
                 if is_on_cursor {
@@ -218,18 +404,17 @@ impl Styler {
         ])))
     }
 
-    pub fn create_units_list_item(
+    // the note comes first, it is what the bookmark was made for
+    pub(crate) fn create_bookmark_item(
         &self,
         index: usize,
         current_line: usize,
-        unit: &ServiceUnits,
+        bookmark: &Bookmark,
     ) -> ListItem<'static> {
         let is_on_cursor = index == current_line;
-        let load_c = get_load_color_str(&unit.load);
-        let active_c = get_active_color_str(&unit.active);
-        let sub_c = get_sub_color_str(&unit.sub);
+        let (marker, marker_style) = self.get_bookmark_marker(Some(bookmark));
 
-        ListItem::from(Text::from(Line::from(vec![
+        let mut spans = vec![
             Span::styled(
                 if is_on_cursor {
                     &self.config.options.cursor_left
@@ -239,55 +424,394 @@ impl Styler {
                 .to_string(),
                 Style::default().fg(self.config.get_palette_color("blue")),
             ),
+            Span::styled(format!("{} ", marker), marker_style),
             Span::styled(
-                "[".to_string(),
+                format!("{} ", bookmark.timestamp),
                 Style::default()
-                    .fg(self.config.get_palette_color("yellow"))
+                    .fg(self.config.get_palette_color("white"))
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(
-                format!("{}", unit.name),
+                format!("{} ", bookmark.unit),
                 Style::default()
-                    .fg(self.config.get_palette_color("white"))
+                    .fg(self.config.get_palette_color("green"))
                     .add_modifier(Modifier::BOLD),
             ),
+        ];
+        if !bookmark.note.is_empty() {
+            spans.push(Span::styled(format!("{} -- ", bookmark.note), marker_style));
+        }
+        spans.push(Span::styled(
+            bookmark.message.clone(),
+            Style::default().fg(self.config.get_palette_color("gray")),
+        ));
+
+        ListItem::from(Text::from(Line::from(spans)))
+    }
+
+    pub(crate) fn create_pattern_item(
+        &self,
+        index: usize,
+        current_line: usize,
+        pattern: &Pattern,
+    ) -> ListItem<'static> {
+        let is_on_cursor = index == current_line;
+        let masked = Style::default().fg(self.config.get_palette_color("blue"));
+
+        let mut spans = vec![
             Span::styled(
-                "] ".to_string(),
+                if is_on_cursor {
+                    &self.config.options.cursor_left
+                } else {
+                    " "
+                }
+                .to_string(),
+                Style::default().fg(self.config.get_palette_color("blue")),
+            ),
+            Span::styled(
+                format!("{:>7} ", pattern.count),
                 Style::default()
                     .fg(self.config.get_palette_color("yellow"))
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(
-                format!("{:?} ", unit.load),
-                Style::default().fg(self.config.get_palette_color(load_c)),
+                format!(
+                    "{} - {} ",
+                    format_timestamp(pattern.first_seen),
+                    format_timestamp(pattern.last_seen)
+                ),
+                Style::default().fg(self.config.get_palette_color("gray")),
             ),
-            Span::styled(
-                format!("{:?} ", unit.active),
+        ];
+
+        // masked tokens stand out from the fixed text of the template
+        for (idx, word) in pattern.template.split(' ').enumerate() {
+            if idx > 0 {
+                spans.push(Span::raw(" "));
+            }
+            let style = if word.contains('<') && word.contains('>') {
+                masked
+            } else {
+                Style::default().fg(self.config.get_palette_color("white"))
+            };
+            spans.push(Span::styled(word.to_string(), style));
+        }
+
+        ListItem::from(Text::from(Line::from(spans)))
+    }
+
+    pub(crate) fn create_fuzzy_list_item(
+        &self,
+        index: usize,
+        current_line: usize,
+        candidate: &FuzzyCandidate,
+        positions: &[usize],
+    ) -> ListItem<'static> {
+        let is_on_cursor = index == current_line;
+        let name_len = candidate.name.chars().count();
+        let highlight = Style::default()
+            .fg(self.config.get_palette_color("yellow"))
+            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+
+        let mut spans = vec![Span::styled(
+            if is_on_cursor {
+                &self.config.options.cursor_left
+            } else {
+                " "
+            }
+            .to_string(),
+            Style::default().fg(self.config.get_palette_color("blue")),
+        )];
+
+        // positions index into "name description"
+        let text = format!("{} {}", candidate.name, candidate.description);
+        spans.extend(text.chars().enumerate().map(|(idx, c)| {
+            let base = if idx < name_len {
                 Style::default()
-                    .fg(self.config.get_palette_color(active_c))
+                    .fg(self.config.get_palette_color("white"))
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(self.config.get_palette_color("gray"))
+            };
+
+            if positions.contains(&idx) {
+                Span::styled(c.to_string(), highlight)
+            } else {
+                Span::styled(c.to_string(), base)
+            }
+        }));
+
+        ListItem::from(Text::from(Line::from(spans)))
+    }
+
+    pub(crate) fn get_unit_cell(
+        &self,
+        column: &TableColumn,
+        unit: &ServiceUnits,
+    ) -> (String, Style) {
+        match column {
+            TableColumn::Name => (
+                unit.name.clone(),
+                Style::default()
+                    .fg(self.config.get_palette_color("white"))
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(
-                format!("{:?} ", unit.sub),
-                Style::default().fg(self.config.get_palette_color(sub_c)),
+            TableColumn::Load => (
+                format!("{:?}", unit.load),
+                Style::default().fg(self
+                    .config
+                    .get_palette_color(get_load_color_str(&unit.load))),
             ),
-            Span::styled(
-                "Message: ".to_string(),
-                Style::default().fg(self.config.get_palette_color("white")),
+            TableColumn::Active => (
+                format!("{:?}", unit.active),
+                Style::default()
+                    .fg(self
+                        .config
+                        .get_palette_color(get_active_color_str(&unit.active)))
+                    .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(
-                format!("{}", unit.description),
+            TableColumn::Sub => (
+                format!("{:?}", unit.sub),
+                Style::default().fg(self.config.get_palette_color(get_sub_color_str(&unit.sub))),
+            ),
+            TableColumn::Description => (
+                unit.description.clone(),
                 Style::default().fg(self.config.get_palette_color("gray")),
             ),
-            Span::styled(
-                if is_on_cursor {
-                    &self.config.options.cursor_right
-                } else {
-                    " "
-                }
-                .to_string(),
+            _ => ("".to_string(), Style::default()),
+        }
+    }
+
+    pub(crate) fn get_file_cell(
+        &self,
+        column: &TableColumn,
+        file: &ServiceUnitFiles,
+    ) -> (String, Style) {
+        match column {
+            TableColumn::Name => (
+                file.name.clone(),
+                Style::default()
+                    .fg(self.config.get_palette_color("white"))
+                    .add_modifier(Modifier::BOLD),
+            ),
+            TableColumn::State => (
+                format!("{:?}", file.state),
+                Style::default().fg(self
+                    .config
+                    .get_palette_color(get_state_color_str(&file.state))),
+            ),
+            TableColumn::Preset => (
+                format!("{:?}", file.preset),
+                Style::default()
+                    .fg(self
+                        .config
+                        .get_palette_color(get_preset_color_str(&file.preset)))
+                    .add_modifier(Modifier::BOLD),
+            ),
+            _ => ("".to_string(), Style::default()),
+        }
+    }
+
+    // in a merged timeline every unit gets its own colour
+    fn get_merged_unit_style(&self, log: &JournalLog, units: &[String]) -> Option<Style> {
+        if units.len() < 2 {
+            return None;
+        }
+
+        let idx = units.iter().position(|unit| *unit == log.unit)?;
+        let color = MERGED_UNIT_COLORS[idx % MERGED_UNIT_COLORS.len()];
+        Some(
+            Style::default()
+                .fg(self.config.get_palette_color(color))
+                .add_modifier(Modifier::BOLD),
+        )
+    }
+
+    pub(crate) fn get_log_cell(
+        &self,
+        column: &TableColumn,
+        log: &JournalLog,
+        units: &[String],
+    ) -> (String, Style) {
+        if let Some(style) = self.get_merged_unit_style(log, units) {
+            match column {
+                TableColumn::Service => return (log.service.clone(), style),
+                TableColumn::Unit => return (log.unit.clone(), style),
+                _ => {}
+            }
+        }
+
+        match column {
+            TableColumn::Timestamp => (
+                log.timestamp.clone(),
+                Style::default()
+                    .fg(self.config.get_palette_color("white"))
+                    .add_modifier(Modifier::BOLD),
+            ),
+            TableColumn::Priority => {
+                let priority_str = map_to_priority_str(&log.priority);
+                (
+                    priority_str.to_string(),
+                    Style::default().fg(self.config.get_priority_color(priority_str)),
+                )
+            }
+            TableColumn::Hostname => (
+                log.hostname.clone(),
                 Style::default().fg(self.config.get_palette_color("blue")),
             ),
-        ])))
+            TableColumn::Service => (
+                log.service.clone(),
+                Style::default()
+                    .fg(self.config.get_palette_color("green"))
+                    .add_modifier(Modifier::BOLD),
+            ),
+            TableColumn::Unit => (
+                log.unit.clone(),
+                Style::default().fg(self.config.get_palette_color("green")),
+            ),
+            TableColumn::Message => (
+                log.log_message.clone(),
+                Style::default().fg(self.config.get_palette_color("gray")),
+            ),
+            _ => ("".to_string(), Style::default()),
+        }
+    }
+
+    // units marked for the merged view are flagged in front of the first cell
+    pub(crate) fn mark_row(
+        &self,
+        mut row: Vec<(String, Style)>,
+        is_marked: bool,
+    ) -> Vec<(String, Style)> {
+        if is_marked {
+            if let Some((text, style)) = row.first_mut() {
+                *text = format!("{}{}", MARK_SYMBOL, text);
+                *style = style.fg(self.config.get_palette_color("yellow"));
+            }
+        }
+        row
+    }
+
+    pub(crate) fn select_row(&self, row: Row<'static>, is_selected: bool) -> Row<'static> {
+        if is_selected {
+            row.style(Style::default().bg(self.config.get_palette_color("gray")))
+        } else {
+            row
+        }
+    }
+
+    // cells are cut to the column widths, the wide column is scrolled first
+    pub(crate) fn create_table_row(
+        &self,
+        index: usize,
+        current_line: Option<usize>,
+        cells: Vec<(String, Style)>,
+        columns: &[TableColumn],
+        widths: &[u16],
+        h_scroll: usize,
+    ) -> Row<'static> {
+        let is_on_cursor = Some(index) == current_line;
+
+        let mut row = vec![self.create_cursor_cell(is_on_cursor, true)];
+
+        row.extend(
+            cells
+                .into_iter()
+                .zip(columns.iter().zip(widths.iter()))
+                .map(|((text, style), (column, width))| {
+                    let text = if column.is_wide() {
+                        scroll(&text, h_scroll)
+                    } else {
+                        text
+                    };
+                    Cell::from(Span::styled(truncate(&text, *width as usize), style))
+                }),
+        );
+
+        row.push(self.create_cursor_cell(is_on_cursor, false));
+
+        Row::new(row)
+    }
+
+    // :set wrap, the wide column takes as many lines as its text needs
+    pub(crate) fn create_wrapped_table_row(
+        &self,
+        index: usize,
+        current_line: Option<usize>,
+        cells: Vec<(String, Style)>,
+        columns: &[TableColumn],
+        widths: &[u16],
+    ) -> Row<'static> {
+        let is_on_cursor = Some(index) == current_line;
+        let mut height = 1;
+
+        let mut row = vec![self.create_cursor_cell(is_on_cursor, true)];
+
+        row.extend(
+            cells
+                .into_iter()
+                .zip(columns.iter().zip(widths.iter()))
+                .map(|((text, style), (column, width))| {
+                    if column.is_wide() {
+                        let lines = wrap(&text, *width as usize);
+                        height = height.max(lines.len());
+                        Cell::from(Text::from(
+                            lines
+                                .into_iter()
+                                .map(|line| Line::styled(line, style))
+                                .collect::<Vec<Line>>(),
+                        ))
+                    } else {
+                        Cell::from(Span::styled(truncate(&text, *width as usize), style))
+                    }
+                }),
+        );
+
+        row.push(self.create_cursor_cell(is_on_cursor, false));
+
+        Row::new(row).height(height as u16)
+    }
+
+    fn create_cursor_cell(&self, is_on_cursor: bool, is_left: bool) -> Cell<'static> {
+        let cursor = match (is_on_cursor, is_left) {
+            (true, true) => self.config.options.cursor_left.clone(),
+            (true, false) => self.config.options.cursor_right.clone(),
+            (false, _) => " ".to_string(),
+        };
+
+        Cell::from(Span::styled(
+            cursor,
+            Style::default().fg(self.config.get_palette_color("blue")),
+        ))
+    }
+
+    // the text goes in the same column that takes the remaining width
+    pub(crate) fn create_separator_row(
+        &self,
+        text: &str,
+        columns: &[TableColumn],
+        widths: &[u16],
+        is_on_cursor: bool,
+    ) -> Row<'static> {
+        let style = Style::default()
+            .fg(self.config.get_palette_color("yellow"))
+            .add_modifier(Modifier::BOLD);
+        let text_column = columns
+            .iter()
+            .position(|column| column.is_wide())
+            .unwrap_or(columns.len().saturating_sub(1));
+
+        let mut row = vec![self.create_cursor_cell(is_on_cursor, true)];
+        row.extend(widths.iter().enumerate().map(|(idx, width)| {
+            let text = if idx == text_column {
+                truncate(text, *width as usize)
+            } else {
+                "─".repeat(*width as usize)
+            };
+            Cell::from(Span::styled(text, style))
+        }));
+        row.push(self.create_cursor_cell(is_on_cursor, false));
+
+        Row::new(row)
     }
 }
@@ -0,0 +1,371 @@
+use crate::core::{journal::JournalLog, sort::SortColumn};
+use crate::ui::ui::View;
+use crate::util::map_to_priority_str;
+use log::error;
+
+const MIN_COLUMN_WIDTH: usize = 4;
+const MIN_WIDE_COLUMN_WIDTH: usize = 20;
+pub const COLUMN_SPACING: u16 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TableColumn {
+    Name,
+    Load,
+    Active,
+    Sub,
+    Description,
+    State,
+    Preset,
+    Timestamp,
+    Priority,
+    Hostname,
+    Service,
+    Unit,
+    Message,
+}
+
+impl TableColumn {
+    fn parse(column: &str, view: &TableView) -> Option<Self> {
+        let column = match column.trim().to_lowercase().as_str() {
+            "name" => Self::Name,
+            "load" => Self::Load,
+            "active" => Self::Active,
+            "sub" => Self::Sub,
+            "description" => Self::Description,
+            "state" => Self::State,
+            "preset" => Self::Preset,
+            "timestamp" => Self::Timestamp,
+            "priority" => Self::Priority,
+            "hostname" => Self::Hostname,
+            "service" => Self::Service,
+            "unit" => Self::Unit,
+            "message" => Self::Message,
+            _ => return None,
+        };
+
+        Self::available_for(view)
+            .contains(&column)
+            .then_some(column)
+    }
+
+    pub fn available_for(view: &TableView) -> &'static [TableColumn] {
+        match view {
+            TableView::Units => &[
+                Self::Name,
+                Self::Load,
+                Self::Active,
+                Self::Sub,
+                Self::Description,
+            ],
+            TableView::Files => &[Self::Name, Self::State, Self::Preset],
+            TableView::Logs => &[
+                Self::Timestamp,
+                Self::Priority,
+                Self::Hostname,
+                Self::Service,
+                Self::Unit,
+                Self::Message,
+            ],
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Name => "NAME",
+            Self::Load => "LOAD",
+            Self::Active => "ACTIVE",
+            Self::Sub => "SUB",
+            Self::Description => "DESCRIPTION",
+            Self::State => "STATE",
+            Self::Preset => "PRESET",
+            Self::Timestamp => "TIME",
+            Self::Priority => "PRIORITY",
+            Self::Hostname => "HOST",
+            Self::Service => "SERVICE",
+            Self::Unit => "UNIT",
+            Self::Message => "MESSAGE",
+        }
+    }
+
+    pub fn sort_column(&self) -> Option<SortColumn> {
+        match self {
+            Self::Name => Some(SortColumn::Name),
+            Self::Load => Some(SortColumn::Load),
+            Self::Active => Some(SortColumn::Active),
+            Self::Sub => Some(SortColumn::Sub),
+            Self::State => Some(SortColumn::State),
+            Self::Preset => Some(SortColumn::Preset),
+            _ => None,
+        }
+    }
+
+    // free text columns take the remaining width and scroll horizontally
+    pub fn is_wide(&self) -> bool {
+        matches!(self, Self::Description | Self::Message)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TableView {
+    Units,
+    Files,
+    Logs,
+}
+
+impl From<&View> for TableView {
+    fn from(view: &View) -> Self {
+        match view {
+            View::ServiceUnits | View::Dashboard => Self::Units,
+            View::ServiceUnitFiles => Self::Files,
+        }
+    }
+}
+
+pub fn parse_columns(columns: &[String], view: TableView) -> Vec<TableColumn> {
+    let parsed: Vec<TableColumn> = columns
+        .iter()
+        .filter_map(|column| {
+            let parsed = TableColumn::parse(column, &view);
+            if parsed.is_none() {
+                error!("Invalid column {:?} for {:?} in config", column, view);
+            }
+            parsed
+        })
+        .collect();
+
+    if parsed.is_empty() {
+        return TableColumn::available_for(&view)
+            .iter()
+            .filter(|column| !matches!(column, TableColumn::Priority | TableColumn::Unit))
+            .copied()
+            .collect();
+    }
+
+    parsed
+}
+
+// the widest cell of each column
+pub fn measure_columns(columns: &[TableColumn], rows: &[Vec<String>]) -> Vec<usize> {
+    (0..columns.len())
+        .map(|idx| {
+            rows.iter()
+                .filter_map(|row| row.get(idx))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+// Every entry is measured so the columns stay put while scrolling, the
+// fields are counted as they are instead of building the cells.
+pub fn measure_log_columns(columns: &[TableColumn], entries: &[JournalLog]) -> Vec<usize> {
+    columns
+        .iter()
+        .map(|column| {
+            entries
+                .iter()
+                .map(|log| get_log_cell_width(column, log))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+fn get_log_cell_width(column: &TableColumn, log: &JournalLog) -> usize {
+    match column {
+        TableColumn::Timestamp => log.timestamp.chars().count(),
+        TableColumn::Priority => map_to_priority_str(&log.priority).chars().count(),
+        TableColumn::Hostname => log.hostname.chars().count(),
+        TableColumn::Service => log.service.chars().count(),
+        TableColumn::Unit => log.unit.chars().count(),
+        TableColumn::Message => log.log_message.chars().count(),
+        _ => 0,
+    }
+}
+
+// the wide column scrolls until the end of its longest text is in view
+pub fn get_h_scroll_limit(columns: &[TableColumn], measured: &[usize], widths: &[u16]) -> usize {
+    columns
+        .iter()
+        .position(|column| column.is_wide())
+        .and_then(|idx| {
+            Some(
+                measured
+                    .get(idx)?
+                    .saturating_sub(*widths.get(idx)? as usize),
+            )
+        })
+        .unwrap_or(0)
+}
+
+// Columns are as wide as their widest cell. The wide column gets what is
+// left of the terminal, and when even that does not fit the widest of the
+// other columns are shrunk first.
+pub fn compute_widths(columns: &[TableColumn], measured: &[usize], available: u16) -> Vec<u16> {
+    let mut widths: Vec<usize> = columns
+        .iter()
+        .zip(measured)
+        .map(|(column, width)| (*width).max(column.title().chars().count() + 2))
+        .collect();
+
+    let available = available as usize;
+    let spacing = columns.len().saturating_sub(1) * COLUMN_SPACING as usize;
+    let wide = columns
+        .iter()
+        .position(|column| column.is_wide())
+        .unwrap_or(columns.len().saturating_sub(1));
+
+    let fixed_width = |widths: &[usize]| -> usize {
+        widths
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| *idx != wide)
+            .map(|(_, width)| width)
+            .sum::<usize>()
+            + spacing
+    };
+
+    while fixed_width(&widths) + MIN_WIDE_COLUMN_WIDTH > available {
+        let widest = widths
+            .iter()
+            .enumerate()
+            .filter(|(idx, width)| *idx != wide && **width > MIN_COLUMN_WIDTH)
+            .max_by_key(|(_, width)| **width)
+            .map(|(idx, _)| idx);
+
+        match widest {
+            Some(idx) => widths[idx] -= 1,
+            None => break,
+        }
+    }
+
+    let remaining = available.saturating_sub(fixed_width(&widths));
+    if let Some(width) = widths.get_mut(wide) {
+        *width = remaining;
+    }
+
+    widths.into_iter().map(|width| width as u16).collect()
+}
+
+pub fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+
+    if width == 0 {
+        return "".to_string();
+    }
+
+    let mut truncated: String = text.chars().take(width - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+pub fn scroll(text: &str, offset: usize) -> String {
+    text.chars().skip(offset).collect()
+}
+
+// breaks at spaces, words longer than the width are split
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    if width == 0 {
+        return vec![];
+    }
+
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split(' ') {
+        let mut word: Vec<char> = word.chars().collect();
+        let line_len = line.chars().count();
+
+        if line_len > 0 && line_len + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut line));
+        } else if line_len > 0 {
+            line.push(' ');
+        }
+
+        while line.chars().count() + word.len() > width {
+            let split = width - line.chars().count();
+            line.extend(word.drain(..split));
+            lines.push(std::mem::take(&mut line));
+        }
+        line.extend(word);
+    }
+
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_at_spaces() {
+        assert_eq!(
+            wrap("the quick brown fox", 10),
+            vec!["the quick", "brown fox"]
+        );
+        assert_eq!(wrap("short", 10), vec!["short"]);
+        assert_eq!(wrap("", 10), vec![""]);
+    }
+
+    #[test]
+    fn splits_long_words() {
+        assert_eq!(wrap("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        // a word that does not fit starts a new line before it is split
+        assert_eq!(wrap("a bcdefg", 4), vec!["a", "bcde", "fg"]);
+    }
+
+    #[test]
+    fn counts_characters_not_bytes() {
+        assert_eq!(wrap("äöü äöü", 3), vec!["äöü", "äöü"]);
+    }
+
+    #[test]
+    fn zero_width_has_no_lines() {
+        assert!(wrap("text", 0).is_empty());
+    }
+
+    #[test]
+    fn wide_column_gets_the_rest() {
+        let units = TableColumn::available_for(&TableView::Units);
+        assert_eq!(
+            compute_widths(units, &[30, 6, 8, 7, 40], 100),
+            vec![30, 6, 8, 7, 45]
+        );
+
+        // without a wide column the last one takes what is left
+        let files = TableColumn::available_for(&TableView::Files);
+        assert_eq!(compute_widths(files, &[20, 9, 8], 60), vec![20, 9, 29]);
+    }
+
+    #[test]
+    fn narrow_terminals_shrink_the_widest_first() {
+        let units = TableColumn::available_for(&TableView::Units);
+        assert_eq!(
+            compute_widths(units, &[30, 6, 8, 7, 40], 60),
+            vec![15, 6, 8, 7, 20]
+        );
+
+        // narrower than the titles, the columns stop at their minimum
+        assert_eq!(
+            compute_widths(units, &[0, 0, 0, 0, 0], 30),
+            vec![4, 4, 4, 4, 10]
+        );
+        assert_eq!(
+            compute_widths(units, &[0, 0, 0, 0, 0], 10),
+            vec![4, 4, 4, 4, 0]
+        );
+    }
+
+    #[test]
+    fn truncates_with_an_ellipsis() {
+        assert_eq!(truncate("nginx.service", 6), "nginx…");
+        assert_eq!(truncate("nginx", 5), "nginx");
+        assert_eq!(truncate("nginx", 0), "");
+        assert_eq!(truncate("", 0), "");
+    }
+}
@@ -1,27 +1,49 @@
 use super::styles::Styler;
 use crate::app::App;
 use crate::core::{
+    bookmarks::{find_bookmark, Bookmark},
+    command::CommandCompletion,
+    dedupe::{get_hidden_lines, DedupeMode, DuplicateRun},
     error::Result,
-    journal::JournalLog,
+    filter::{UnitFilter, QUICK_FILTERS},
+    histogram::{Histogram, HISTOGRAM_BUCKETS},
+    invocation::{build_log_rows, format_duration, InvocationGroup, InvocationInfo, LogRow},
+    journal::{format_timestamp, JournalLog},
+    matches::{JournalMatch, MATCH_FIELDS},
+    motion::{PendingKeys, Viewport},
+    mouse::{Click, ClickArea, MouseTargets},
+    search::SearchRow,
     system::{ServiceUnitFiles, ServiceUnits},
 };
 use crate::ui::layouts::center;
+use crate::ui::table::{
+    compute_widths, get_h_scroll_limit, measure_columns, measure_log_columns, parse_columns, wrap,
+    TableColumn, TableView, COLUMN_SPACING,
+};
 use crate::util::{
     get_active_color_str, get_load_color_str, get_preset_color_str, get_state_color_str,
-    get_sub_color_str, map_to_priority_str, DOCS, HELP,
+    get_sub_color_str, map_to_priority_str, DOCS,
 };
 use log::info;
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Flex, Layout, Margin, Rect},
     style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Clear, List, ListItem, Paragraph, Widget, Wrap},
+    widgets::{
+        Bar, BarChart, BarGroup, Block, Cell, Clear, List, ListItem, Paragraph, Row, Table, Widget,
+        Wrap,
+    },
     Frame,
 };
+use std::time::{Duration, Instant};
+
+const H_SCROLL_STEP: usize = 8;
+const NOTIFICATION_DURATION: Duration = Duration::from_secs(4);
 
 // logs view could be added here
 #[derive(Debug, Clone, PartialEq)]
 pub enum View {
+    Dashboard,
     ServiceUnits,
     ServiceUnitFiles,
 }
@@ -33,6 +55,20 @@ pub enum CurrentLine {
     ServiceUnitFile(ServiceUnitFiles),
 }
 
+// a short message in the bottom bar after an action finished
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub message: String,
+    pub is_error: bool,
+    shown_at: Instant,
+}
+
+impl Notification {
+    pub fn is_visible(&self) -> bool {
+        self.shown_at.elapsed() < NOTIFICATION_DURATION
+    }
+}
+
 #[derive(Debug)]
 pub struct UI {
     pub view: View,
@@ -41,10 +77,56 @@ pub struct UI {
     pub is_in_logs: bool,
     pub is_in_search_mode: bool,
     pub is_showing_docs: bool,
+    pub is_in_global_search_mode: bool,
+    pub is_showing_global_search: bool,
+    pub is_editing_search_since: bool,
+    pub is_showing_fuzzy_finder: bool,
+    pub is_showing_filters: bool,
+    pub is_showing_match_builder: bool,
+    pub is_showing_patterns: bool,
+    pub is_in_export_prompt: bool,
+    pub export_path: String,
+    pub notification: Option<Notification>,
+    pub visual_anchor: Option<usize>,
+    pub is_in_bundle_prompt: bool,
+    pub bundle_since: String,
+    pub is_in_jump_prompt: bool,
+    pub jump_time: String,
+    pub is_showing_bookmarks: bool,
+    pub bookmarks_line: usize,
+    pub is_in_annotation_prompt: bool,
+    pub annotation: String,
+    pub is_in_confirm_prompt: bool,
+    pub confirm_question: String,
+    pub is_in_command_mode: bool,
+    pub command_line: String,
+    pub command_completion: Option<CommandCompletion>,
+    pub is_wrapping: bool,
+    pub pending_keys: PendingKeys,
+    // drawing keeps the rows on screen here, a cell as it only borrows the ui
+    pub services_viewport: std::cell::Cell<Viewport>,
+    pub logs_viewport: std::cell::Cell<Viewport>,
+    pub mouse_targets: std::cell::RefCell<MouseTargets>,
+    pub last_click: Option<Click>,
+    pub is_grouping_invocations: bool,
+    pub dedupe: DedupeMode,
+    pub is_in_histogram: bool,
+    pub histogram_bucket: usize,
     pub search_query: String,
     pub search_matches: Vec<CurrentLine>,
+    pub global_search_query: String,
+    pub global_search_since: String,
     pub selected_priority: Option<u8>,
     pub current_line: usize,
+    pub services_line: usize,
+    pub logs_line: usize,
+    pub h_scroll: usize,
+}
+
+impl Default for UI {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl UI {
@@ -56,10 +138,49 @@ impl UI {
             is_showing_docs: false,
             is_in_logs: false,
             is_in_search_mode: false,
+            is_in_global_search_mode: false,
+            is_showing_global_search: false,
+            is_editing_search_since: false,
+            is_showing_fuzzy_finder: false,
+            is_showing_filters: false,
+            is_showing_match_builder: false,
+            is_showing_patterns: false,
+            is_in_export_prompt: false,
+            export_path: "".to_string(),
+            notification: None,
+            visual_anchor: None,
+            is_in_bundle_prompt: false,
+            bundle_since: "".to_string(),
+            is_in_jump_prompt: false,
+            jump_time: "".to_string(),
+            is_showing_bookmarks: false,
+            bookmarks_line: 0,
+            is_in_annotation_prompt: false,
+            annotation: "".to_string(),
+            is_in_confirm_prompt: false,
+            confirm_question: "".to_string(),
+            is_in_command_mode: false,
+            command_line: "".to_string(),
+            command_completion: None,
+            is_wrapping: false,
+            pending_keys: PendingKeys::default(),
+            services_viewport: Default::default(),
+            logs_viewport: Default::default(),
+            mouse_targets: Default::default(),
+            last_click: None,
+            is_grouping_invocations: false,
+            dedupe: DedupeMode::Off,
+            is_in_histogram: false,
+            histogram_bucket: 0,
             search_query: "".to_string(),
             search_matches: vec![],
+            global_search_query: "".to_string(),
+            global_search_since: "".to_string(),
             selected_priority: Some(5),
             current_line: 0,
+            services_line: 0,
+            logs_line: 0,
+            h_scroll: 0,
         }
     }
 
@@ -91,21 +212,153 @@ impl UI {
         self.is_in_search_mode = state;
     }
 
+    pub fn set_is_in_global_search_mode(&mut self, state: bool) {
+        self.is_in_global_search_mode = state;
+    }
+
+    pub fn set_is_showing_global_search(&mut self, state: bool) {
+        self.is_showing_global_search = state;
+    }
+
+    pub fn set_is_showing_fuzzy_finder(&mut self, state: bool) {
+        self.is_showing_fuzzy_finder = state;
+    }
+
+    pub fn set_is_showing_filters(&mut self, state: bool) {
+        self.is_showing_filters = state;
+    }
+
+    pub fn set_is_showing_match_builder(&mut self, state: bool) {
+        self.is_showing_match_builder = state;
+    }
+
+    pub fn set_is_showing_patterns(&mut self, state: bool) {
+        self.is_showing_patterns = state;
+    }
+
+    pub fn set_is_in_export_prompt(&mut self, state: bool) {
+        self.is_in_export_prompt = state;
+    }
+
+    pub fn set_is_in_bundle_prompt(&mut self, state: bool) {
+        self.is_in_bundle_prompt = state;
+    }
+
+    pub fn set_is_in_jump_prompt(&mut self, state: bool) {
+        self.is_in_jump_prompt = state;
+        self.jump_time.clear();
+    }
+
+    pub fn set_is_showing_bookmarks(&mut self, state: bool) {
+        self.is_showing_bookmarks = state;
+        self.bookmarks_line = 0;
+    }
+
+    pub fn set_is_in_annotation_prompt(&mut self, state: bool) {
+        self.is_in_annotation_prompt = state;
+    }
+
+    pub fn set_is_in_confirm_prompt(&mut self, state: bool) {
+        self.is_in_confirm_prompt = state;
+    }
+
+    pub fn set_is_in_command_mode(&mut self, state: bool) {
+        self.is_in_command_mode = state;
+        self.command_line.clear();
+        self.command_completion = None;
+    }
+
+    pub fn notify(&mut self, message: String, is_error: bool) {
+        self.notification = Some(Notification {
+            message,
+            is_error,
+            shown_at: Instant::now(),
+        });
+    }
+
     pub fn set_priority(&mut self, priority: u8) {
         self.selected_priority = Some(priority);
         self.current_line = 0;
+        self.visual_anchor = None;
     }
 
     pub fn set_current_line(&mut self, position: usize) {
         self.current_line = position;
     }
 
+    pub fn get_viewport(&self) -> Viewport {
+        if self.is_in_logs {
+            self.logs_viewport.get()
+        } else {
+            self.services_viewport.get()
+        }
+    }
+
+    // page motions move the rows too, the next draw keeps the cursor in them
+    pub fn scroll_viewport(&mut self, rows: isize) {
+        let viewport = if self.is_in_logs {
+            &self.logs_viewport
+        } else {
+            &self.services_viewport
+        };
+        let mut moved = viewport.get();
+        moved.offset = moved.offset.saturating_add_signed(rows);
+        viewport.set(moved);
+    }
+
     pub fn move_cursor_down(&mut self, max: usize) {
         if self.current_line < max.saturating_sub(1) {
             self.current_line += 1;
         }
     }
 
+    // the services cursor is kept while logs are open and put back after
+    pub fn open_logs(&mut self) {
+        if !self.is_in_logs {
+            self.services_line = self.current_line;
+        }
+        self.is_in_logs = true;
+        self.logs_line = 0;
+        self.current_line = 0;
+    }
+
+    pub fn close_logs(&mut self) {
+        self.is_in_logs = false;
+        self.logs_line = 0;
+        self.current_line = self.services_line;
+        self.h_scroll = 0;
+        self.is_in_histogram = false;
+        self.visual_anchor = None;
+    }
+
+    // moving between the panes of the split layout keeps both cursors
+    pub fn focus_services(&mut self) {
+        if self.is_in_logs {
+            self.logs_line = self.current_line;
+            self.current_line = self.services_line;
+            self.is_in_logs = false;
+        }
+    }
+
+    pub fn focus_logs(&mut self) {
+        if !self.is_in_logs {
+            self.services_line = self.current_line;
+            self.current_line = self.logs_line;
+            self.is_in_logs = true;
+        }
+    }
+
+    pub fn scroll_right(&mut self) {
+        self.h_scroll = (self.h_scroll + H_SCROLL_STEP).min(self.get_viewport().h_scroll_limit);
+    }
+
+    pub fn scroll_left(&mut self) {
+        self.h_scroll = self
+            .h_scroll
+            .min(self.get_viewport().h_scroll_limit)
+            .saturating_sub(H_SCROLL_STEP);
+    }
+
     pub fn move_cursor_up(&mut self) {
         if self.current_line > 0 {
             self.current_line -= 1;
@@ -122,14 +375,16 @@ impl UI {
                 .get(self.selected_priority.as_ref()?)?
                 .get(self.current_line)
                 .map(|log| CurrentLine::Log(log.clone()));
-        } else if let Some((u, f)) = app.services.as_ref() {
+        } else if let Some((_, f)) = app.services.as_ref() {
             let service_line = match self.view {
-                View::ServiceUnits => u
+                View::ServiceUnits => app
+                    .get_visible_units()
                     .get(self.current_line)
-                    .map(|unit| CurrentLine::ServiceUnit(unit.clone())),
+                    .map(|unit| CurrentLine::ServiceUnit((*unit).clone())),
                 View::ServiceUnitFiles => f
                     .get(self.current_line)
                     .map(|file| CurrentLine::ServiceUnitFile(file.clone())),
+                View::Dashboard => None,
             };
 
             return service_line;
@@ -137,16 +392,111 @@ impl UI {
         None
     }
 
-    pub fn get_log_message(&self, app: &App) -> Option<String> {
-        match self.get_current_line(app) {
-            line => match line {
-                Some(CurrentLine::Log(l)) => Some(format!("{:?}", l.log_message)),
-                _ => None,
-            },
-        }
+    // V, the selection runs from the anchor to the cursor
+    pub fn toggle_visual_mode(&mut self) {
+        self.visual_anchor = match self.visual_anchor {
+            Some(_) => None,
+            None => Some(self.current_line),
+        };
+    }
+
+    pub fn get_selection(&self) -> Option<(usize, usize)> {
+        let anchor = self.visual_anchor?;
+        Some((anchor.min(self.current_line), anchor.max(self.current_line)))
+    }
+}
+
+// borders and the cursor columns on both sides are not available to cells
+fn table_available_width(area: Rect, styler: &Styler) -> u16 {
+    let cursor_width = styler.config.options.cursor_left.chars().count()
+        + styler.config.options.cursor_right.chars().count();
+
+    area.width
+        .saturating_sub(2)
+        .saturating_sub(cursor_width as u16)
+        .saturating_sub(2 * COLUMN_SPACING)
+}
+
+fn table_constraints(styler: &Styler, widths: &[u16]) -> Vec<Constraint> {
+    let mut constraints = vec![Constraint::Length(
+        styler.config.options.cursor_left.chars().count() as u16,
+    )];
+    constraints.extend(widths.iter().map(|width| Constraint::Length(*width)));
+    constraints.push(Constraint::Length(
+        styler.config.options.cursor_right.chars().count() as u16,
+    ));
+    constraints
+}
+
+fn build_table(
+    styler: &Styler,
+    columns: &[TableColumn],
+    cells: Vec<(usize, Vec<(String, Style)>)>,
+    (skip, take): (usize, usize),
+    (current_line, h_scroll): (Option<usize>, usize),
+    widths: &[u16],
+    header: Row<'static>,
+) -> Table<'static> {
+    let rows: Vec<Row> = cells
+        .into_iter()
+        .skip(skip)
+        .take(take)
+        .map(|(idx, row)| {
+            styler.create_table_row(idx, current_line, row, columns, widths, h_scroll)
+        })
+        .collect();
+
+    Table::new(rows, table_constraints(styler, widths))
+        .header(header)
+        .column_spacing(COLUMN_SPACING)
+}
+
+// borders and the header row are not available to rows
+fn get_display_lines(area: Rect) -> usize {
+    area.height.saturating_sub(3) as usize
+}
+
+// the rows stay where they were until the cursor leaves them
+fn get_scroll_offset(previous: usize, cursor: usize, display_lines: usize, total: usize) -> usize {
+    previous.min(total.saturating_sub(display_lines)).clamp(
+        cursor.saturating_sub(display_lines.saturating_sub(1)),
+        cursor,
+    )
+}
+
+// the rows of a bordered table, below its column titles
+fn get_table_body(area: Rect) -> Rect {
+    let inner = area.inner(Margin::new(1, 1));
+    Rect {
+        y: inner.y.saturating_add(1),
+        height: inner.height.saturating_sub(1),
+        ..inner
     }
 }
 
+// The header is centered on the top border like a block title, its tabs
+// are every other span with the slashes in between.
+fn get_tab_areas(area: Rect, header: &Line) -> Vec<(Rect, View)> {
+    let titles = Rect {
+        height: 1,
+        ..area.inner(Margin::new(1, 0))
+    };
+    let start = titles.x + titles.width.saturating_sub(header.width() as u16) / 2;
+    let views = [View::Dashboard, View::ServiceUnits, View::ServiceUnitFiles];
+
+    header
+        .spans
+        .iter()
+        .scan(start, |x, span| {
+            let tab = Rect::new(*x, titles.y, span.width() as u16, 1);
+            *x = x.saturating_add(tab.width);
+            Some(tab.intersection(titles))
+        })
+        .step_by(2)
+        .zip(views)
+        .collect()
+}
+
 fn render_after_clear<T: Widget>(f: &mut Frame<'_>, clearable: Rect, w: T) {
     f.render_widget(Clear, clearable);
     f.render_widget(w, clearable);
@@ -154,6 +504,8 @@ fn render_after_clear<T: Widget>(f: &mut Frame<'_>, clearable: Rect, w: T) {
 
 // handle the result/error
 pub fn draw_ui(frame: &mut Frame<'_>, app: &App, styler: &Styler) -> Result<()> {
+    app.ui.mouse_targets.replace(MouseTargets::default());
+
     let terminal_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Percentage(97), Constraint::Percentage(3)])
@@ -161,123 +513,1269 @@ pub fn draw_ui(frame: &mut Frame<'_>, app: &App, styler: &Styler) -> Result<()>
     let content_area = terminal_layout
         .first()
         .expect("Error getting terminal layout")
-        .clone();
+        .to_owned();
     let action_area = terminal_layout
         .get(1)
         .expect("Error getting instructions")
-        .clone();
-    let display_lines = frame.area().height.saturating_sub(6) as usize;
-    let scroll_offset = if app.ui.current_line >= display_lines - 2 {
-        app.ui.current_line - (display_lines - 3)
+        .to_owned();
+
+    let content_area = if app.tabs.is_empty() {
+        content_area
     } else {
-        0
+        let [tab_area, content_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(content_area);
+        render_after_clear(
+            frame,
+            tab_area,
+            Paragraph::new(styler.get_tab_bar(&app.tabs, app.active_tab)),
+        );
+        content_area
+    };
+
+    match styler.config.options.get_split_direction() {
+        Some(direction) => {
+            let ratio = styler.config.options.split_ratio.clamp(10, 90);
+            let panes = Layout::default()
+                .direction(direction)
+                .constraints([
+                    Constraint::Percentage(ratio),
+                    Constraint::Percentage(100 - ratio),
+                ])
+                .split(content_area);
+
+            let services_line = if app.ui.is_in_logs {
+                app.ui.services_line
+            } else {
+                app.ui.current_line
+            };
+            draw_services_pane(frame, panes[0], app, styler, services_line);
+
+            // the opened service keeps its logs, any other unit is previewed
+            let highlighted = app.get_highlighted_service();
+            if app.ui.is_in_logs || (app.logs.is_some() && highlighted == app.selected_service) {
+                let logs_line = if app.ui.is_in_logs {
+                    app.ui.current_line
+                } else {
+                    app.ui.logs_line
+                };
+                draw_logs_pane(frame, panes[1], app, styler, logs_line);
+            } else {
+                draw_preview_pane(frame, panes[1], app, styler, highlighted);
+            }
+        }
+        None => {
+            if app.ui.is_in_logs {
+                draw_logs_pane(frame, content_area, app, styler, app.ui.current_line);
+            } else {
+                draw_services_pane(frame, content_area, app, styler, app.ui.current_line);
+            }
+        }
+    }
+
+    let bottom_area = styler.get_bottom_info(&app.ui);
+    render_after_clear(frame, action_area, bottom_area);
+
+    Ok(())
+}
+
+// separators between invocations are given with the rows they are laid out in
+struct LogTable<'a> {
+    entries: &'a [JournalLog],
+    units: &'a [String],
+    groups: Option<(&'a [LogRow], &'a [String])>,
+    duplicates: &'a [DuplicateRun],
+    bookmarks: &'a [Bookmark],
+    selection: Option<(usize, usize)>,
+    wrap: bool,
+    // the rows of the last draw are kept until the cursor leaves them
+    offset: usize,
+}
+
+fn draw_log_table(
+    frame: &mut Frame<'_>,
+    area: Rect,
+    styler: &Styler,
+    log_table: LogTable<'_>,
+    (current_line, h_scroll): (Option<usize>, usize),
+    block: Block<'static>,
+    style: Style,
+) -> (Viewport, Vec<Option<usize>>) {
+    if log_table.entries.is_empty() {
+        let table = Table::new(
+            vec![Row::new(vec![Cell::from(""), Cell::from("No log entries")])],
+            [Constraint::Length(1), Constraint::Fill(1)],
+        );
+        render_after_clear(frame, area, table.block(block).style(style));
+        return (Viewport::default(), vec![]);
+    }
+
+    let display_lines = get_display_lines(area);
+    let columns = parse_columns(&styler.config.columns.logs, TableView::Logs);
+
+    // collapsed duplicates leave only their first entry in the rows
+    let hidden = get_hidden_lines(log_table.duplicates);
+    let rows: Option<Vec<LogRow>> = match log_table.groups {
+        Some((rows, _)) => Some(
+            rows.iter()
+                .filter(|row| !matches!(row, LogRow::Entry(idx) if hidden.contains(idx)))
+                .copied()
+                .collect(),
+        ),
+        None if !hidden.is_empty() => Some(
+            (0..log_table.entries.len())
+                .filter(|idx| !hidden.contains(idx))
+                .map(LogRow::Entry)
+                .collect(),
+        ),
+        None => None,
     };
 
-    if app.ui.is_in_logs {
-        let priority = &app.ui.selected_priority.unwrap_or_default();
-        let priority_str = map_to_priority_str(priority);
-        let priority_style = Style::default().fg(styler.config.get_priority_color(priority_str));
+    // separators take table rows too, the scroll follows the cursor's row
+    let cursor_row = match (&rows, current_line) {
+        (Some(rows), Some(line)) => rows.iter().position(|row| row.get_entry() == Some(line)),
+        (_, line) => line,
+    };
+    // wrapped rows take several lines, the rows around the cursor are measured
+    // before the first one is picked
+    let total_rows = rows
+        .as_ref()
+        .map(|rows| rows.len())
+        .unwrap_or(log_table.entries.len());
+    let (skip, take) = match cursor_row {
+        Some(row) if log_table.wrap => (row.saturating_sub(display_lines), display_lines * 2),
+        Some(row) => (
+            get_scroll_offset(log_table.offset, row, display_lines, total_rows),
+            display_lines,
+        ),
+        None => (0, display_lines),
+    };
+    let visible_rows: Vec<LogRow> = match &rows {
+        Some(rows) => rows.iter().skip(skip).take(take).copied().collect(),
+        None => (skip..log_table.entries.len())
+            .take(take)
+            .map(LogRow::Entry)
+            .collect(),
+    };
 
-        let logs_items: Vec<ListItem> = if let Some(logs_arc) = &app.logs {
-            let logs_map = logs_arc.lock().unwrap();
-            if let Some(log_entries) = logs_map.get(priority) {
-                log_entries
+    // only the visible logs are turned into cells
+    let cells: Vec<(usize, Vec<(String, Style)>)> = visible_rows
+        .iter()
+        .filter_map(|row| match row {
+            LogRow::Entry(idx) => log_table.entries.get(*idx).map(|log| {
+                let run = log_table
+                    .duplicates
                     .iter()
+                    .find(|run| run.first_line == *idx);
+                let bookmark = find_bookmark(log_table.bookmarks, &log.cursor);
+                let cells = columns
+                    .iter()
+                    .map(|column| {
+                        let (text, style) = styler.get_log_cell(column, log, log_table.units);
+                        if *column != TableColumn::Message {
+                            return (text, style);
+                        }
+                        let prefixes = [
+                            run.map(get_duplicate_counter),
+                            bookmark.map(get_bookmark_label),
+                        ];
+                        let parts: Vec<String> =
+                            prefixes.into_iter().flatten().chain([text]).collect();
+                        (parts.join(" "), style)
+                    })
+                    .collect();
+                (*idx, cells)
+            }),
+            LogRow::Separator { .. } => None,
+        })
+        .collect();
+
+    // every entry is measured, not only the visible ones
+    let measured = measure_log_columns(&columns, log_table.entries);
+    let widths = compute_widths(&columns, &measured, table_available_width(area, styler));
+
+    let (offset, visible_rows, heights, cells) = if log_table.wrap {
+        let (first, visible_rows, heights, cells) = get_wrapped_rows(
+            visible_rows,
+            cells,
+            cursor_row.map(|row| row - skip),
+            (&columns, &widths),
+            display_lines,
+        );
+        (skip + first, visible_rows, heights, cells)
+    } else {
+        let heights = vec![1; visible_rows.len()];
+        (skip, visible_rows, heights, cells)
+    };
+    let viewport = Viewport {
+        offset,
+        height: display_lines,
+        first: visible_rows.iter().find_map(|row| row.get_entry()),
+        last: visible_rows.iter().rev().find_map(|row| row.get_entry()),
+        h_scroll_limit: get_h_scroll_limit(&columns, &measured, &widths),
+    };
+    let lines: Vec<Option<usize>> = visible_rows
+        .iter()
+        .zip(heights)
+        .flat_map(|(row, height)| std::iter::repeat_n(row.get_entry(), height))
+        .take(display_lines)
+        .collect();
+
+    let mut cells = cells.into_iter();
+    let rows: Vec<Row> = visible_rows
+        .iter()
+        .filter_map(|row| match row {
+            LogRow::Entry(_) => cells.next().map(|(idx, cells)| {
+                let row = if log_table.wrap {
+                    styler.create_wrapped_table_row(idx, current_line, cells, &columns, &widths)
+                } else {
+                    styler.create_table_row(idx, current_line, cells, &columns, &widths, h_scroll)
+                };
+                let is_selected = log_table
+                    .selection
+                    .is_some_and(|(first, last)| (first..=last).contains(&idx));
+                styler.select_row(row, is_selected)
+            }),
+            LogRow::Separator { group, entry } => {
+                let text = log_table
+                    .groups
+                    .and_then(|(_, separators)| separators.get(*group))?;
+                let text = format!("{} {}", if entry.is_some() { "▶" } else { "▼" }, text);
+                Some(styler.create_separator_row(
+                    &text,
+                    &columns,
+                    &widths,
+                    entry.is_some() && *entry == current_line,
+                ))
+            }
+        })
+        .collect();
+
+    let table = Table::new(rows, table_constraints(styler, &widths))
+        .header(styler.get_column_titles(&columns, None))
+        .column_spacing(COLUMN_SPACING);
+
+    render_after_clear(frame, area, table.block(block).style(style));
+    (viewport, lines)
+}
+
+type LogCells = Vec<(usize, Vec<(String, Style)>)>;
+
+// The cursor row ends up as low as it fits, like the unwrapped table scrolls,
+// and the rows after it fill the remaining lines.
+fn get_wrapped_rows(
+    rows: Vec<LogRow>,
+    cells: LogCells,
+    cursor_row: Option<usize>,
+    (columns, widths): (&[TableColumn], &[u16]),
+    display_lines: usize,
+) -> (usize, Vec<LogRow>, Vec<usize>, LogCells) {
+    let wide = columns.iter().position(|column| column.is_wide());
+    let mut entry_cells = cells.iter();
+    let heights: Vec<usize> = rows
+        .iter()
+        .map(|row| match (row, wide) {
+            (LogRow::Entry(_), Some(wide)) => entry_cells
+                .next()
+                .and_then(|(_, cells)| cells.get(wide))
+                .map(|(text, _)| wrap(text, widths[wide] as usize).len().max(1))
+                .unwrap_or(1),
+            _ => 1,
+        })
+        .collect();
+
+    let cursor_row = cursor_row.unwrap_or(0).min(rows.len().saturating_sub(1));
+    let mut first = cursor_row;
+    let mut used = heights.get(cursor_row).copied().unwrap_or(0);
+    while first > 0 && used + heights[first - 1] <= display_lines {
+        first -= 1;
+        used += heights[first];
+    }
+
+    let mut last = first;
+    let mut used = 0;
+    while last < rows.len() && (last == first || used + heights[last] <= display_lines) {
+        used += heights[last];
+        last += 1;
+    }
+
+    let skipped_entries = rows[..first]
+        .iter()
+        .filter(|row| matches!(row, LogRow::Entry(_)))
+        .count();
+    let cells = cells.into_iter().skip(skipped_entries).collect();
+    (
+        first,
+        rows[first..last].to_vec(),
+        heights[first..last].to_vec(),
+        cells,
+    )
+}
+
+// the note is shown in front of the message it belongs to
+fn get_bookmark_label(bookmark: &Bookmark) -> String {
+    if bookmark.note.is_empty() {
+        bookmark.get_marker().to_string()
+    } else {
+        format!("{} {} --", bookmark.get_marker(), bookmark.note)
+    }
+}
+
+fn get_duplicate_counter(run: &DuplicateRun) -> String {
+    format!("×{} ({})", run.count, format_duration(run.end - run.start))
+}
+
+fn draw_histogram(
+    frame: &mut Frame<'_>,
+    area: Rect,
+    app: &App,
+    styler: &Styler,
+    histogram: &Histogram,
+) {
+    let bar_width = (area.width.saturating_sub(2) / HISTOGRAM_BUCKETS as u16).max(1);
+    let gray = styler.config.get_palette_color("gray");
+
+    let bars: Vec<Bar> = histogram
+        .buckets
+        .iter()
+        .enumerate()
+        .map(|(idx, bucket)| {
+            let color = bucket
+                .top_priority()
+                .map(|p| styler.config.get_priority_color(map_to_priority_str(&p)))
+                .unwrap_or(gray);
+            let style = if app.ui.is_in_histogram && idx == app.ui.histogram_bucket {
+                Style::default()
+                    .fg(styler.config.get_palette_color("white"))
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(color)
+            };
+
+            Bar::default()
+                .value(bucket.total() as u64)
+                .style(style)
+                .text_value("".to_string())
+        })
+        .collect();
+
+    let title = if app.ui.is_in_histogram {
+        let idx = app.ui.histogram_bucket;
+        let bucket = &histogram.buckets[idx];
+        let start = histogram.get_bucket_start(idx);
+        format!(
+            " {} - {} · {} entries · {} errors ",
+            format_timestamp(start),
+            format_timestamp(start + histogram.width),
+            bucket.total(),
+            // emerg to err
+            bucket.counts[..4].iter().sum::<usize>()
+        )
+    } else {
+        format!(
+            " {} - {} ",
+            format_timestamp(histogram.start),
+            format_timestamp(histogram.get_bucket_start(HISTOGRAM_BUCKETS)),
+        )
+    };
+
+    let block = Block::bordered()
+        .title(title)
+        .border_style(styler.get_pane_border_style(app.ui.is_in_histogram));
+    let [chart_area] =
+        Layout::horizontal([Constraint::Length(bar_width * HISTOGRAM_BUCKETS as u16)])
+            .flex(Flex::Center)
+            .areas(block.inner(area));
+
+    let chart = BarChart::default()
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(bar_width)
+        .bar_gap(0);
+
+    render_after_clear(frame, area, block);
+    frame.render_widget(chart, chart_area);
+}
+
+fn get_invocation_separator(group: &InvocationGroup, info: Option<&InvocationInfo>) -> String {
+    let mut parts = vec![];
+
+    match info {
+        Some(info) => {
+            let id: String = group.id.chars().take(8).collect();
+            parts.push(format!("run {}", id));
+            parts.push(format!("started {}", format_timestamp(info.start)));
+            parts.push(format!("ran {}", format_duration(info.end - info.start)));
+            if let Some(exit_status) = &info.exit_status {
+                parts.push(exit_status.clone());
+            }
+        }
+        None => parts.push("no invocation".to_string()),
+    }
+
+    parts.push(format!("{} entries", group.count));
+    parts.join(" · ")
+}
+
+fn draw_logs_pane(
+    frame: &mut Frame<'_>,
+    area: Rect,
+    app: &App,
+    styler: &Styler,
+    current_line: usize,
+) {
+    let priority = &app.ui.selected_priority.unwrap_or_default();
+    let priority_str = map_to_priority_str(priority);
+    let priority_style = Style::default().fg(styler.config.get_priority_color(priority_str));
+
+    let block = Block::bordered()
+        .title_alignment(Alignment::Center)
+        .title(format!(
+            "  {} -- {}/{}{}{}{}{}  ",
+            app.selected_service.as_deref().unwrap_or("Logs"),
+            priority,
+            priority_str,
+            app.log_window
+                .label()
+                .map(|label| format!(" ({})", label))
+                .unwrap_or_default(),
+            if app.journal_matches.is_empty() {
+                "".to_string()
+            } else {
+                let labels: Vec<String> = app
+                    .journal_matches
+                    .iter()
+                    .map(|journal_match| journal_match.label())
+                    .collect();
+                format!(" [{}]", labels.join(" "))
+            },
+            if app.ui.dedupe == DedupeMode::Off {
+                "".to_string()
+            } else {
+                format!(" ×{}", app.ui.dedupe.label())
+            },
+            if app.is_active_tab_loading() {
+                " (loading)"
+            } else {
+                ""
+            }
+        ))
+        .style(priority_style)
+        .border_style(styler.get_pane_border_style(app.ui.is_in_logs));
+
+    // the chart and grouping lock the logs themselves, so they are done first
+    let histogram = app.get_histogram();
+    let area = match histogram {
+        Some(histogram) if styler.config.options.histogram_height > 0 => {
+            let [chart_area, area] = Layout::vertical([
+                Constraint::Length(styler.config.options.histogram_height),
+                Constraint::Min(0),
+            ])
+            .areas(area);
+            draw_histogram(frame, chart_area, app, styler, &histogram);
+            area
+        }
+        _ => area,
+    };
+
+    let groups = app.get_invocation_groups();
+    let duplicates = app.get_collapsed_runs();
+    let rows = groups
+        .as_ref()
+        .map(|groups| build_log_rows(groups, &app.folded_invocations));
+    let separators: Vec<String> = groups
+        .into_iter()
+        .flatten()
+        .map(|group| get_invocation_separator(group, app.invocations.get(&group.id)))
+        .collect();
+
+    let logs_map = app.logs.as_ref().and_then(|logs_arc| logs_arc.lock().ok());
+    let entries = logs_map
+        .as_ref()
+        .and_then(|logs_map| logs_map.get(priority))
+        .map(|entries| entries.as_slice())
+        .unwrap_or_default();
+
+    let (viewport, lines) = draw_log_table(
+        frame,
+        area,
+        styler,
+        LogTable {
+            entries,
+            units: &app.selected_units,
+            groups: rows.as_deref().map(|rows| (rows, separators.as_slice())),
+            duplicates: &duplicates,
+            bookmarks: &app.bookmarks,
+            selection: app.ui.get_selection().filter(|_| app.ui.is_in_logs),
+            wrap: app.ui.is_wrapping,
+            offset: app.ui.logs_viewport.get().offset,
+        },
+        (Some(current_line), app.ui.h_scroll),
+        block,
+        priority_style,
+    );
+    app.ui.logs_viewport.set(viewport);
+    app.ui.mouse_targets.borrow_mut().logs = Some(ClickArea::new(get_table_body(area), lines));
+}
+
+fn draw_preview_pane(
+    frame: &mut Frame<'_>,
+    area: Rect,
+    app: &App,
+    styler: &Styler,
+    service: Option<String>,
+) {
+    let style = Style::default().fg(styler.config.get_palette_color("gray"));
+    let block = Block::bordered()
+        .title_alignment(Alignment::Center)
+        .title(format!(
+            "  Preview: {}  ",
+            service.as_deref().unwrap_or("no service")
+        ))
+        .style(style)
+        .border_style(styler.get_pane_border_style(false));
+
+    let preview = app.preview.lock().ok();
+    let entries = preview
+        .as_ref()
+        .and_then(|preview| preview.as_ref())
+        .filter(|preview| Some(&preview.service) == service.as_ref())
+        .map(|preview| preview.logs.as_slice());
+
+    match entries {
+        Some(entries) => {
+            draw_log_table(
+                frame,
+                area,
+                styler,
+                LogTable {
+                    entries,
+                    units: &[],
+                    groups: None,
+                    duplicates: &[],
+                    bookmarks: &app.bookmarks,
+                    selection: None,
+                    wrap: app.ui.is_wrapping,
+                    offset: 0,
+                },
+                (None, app.ui.h_scroll),
+                block,
+                style,
+            );
+        }
+        None => {
+            let loading = Paragraph::new(" Loading preview...")
+                .block(block)
+                .style(style);
+            render_after_clear(frame, area, loading);
+        }
+    }
+}
+
+fn draw_services_pane(
+    frame: &mut Frame<'_>,
+    area: Rect,
+    app: &App,
+    styler: &Styler,
+    current_line: usize,
+) {
+    if app.ui.view == View::Dashboard {
+        draw_dashboard(frame, area, app, styler);
+        return;
+    }
+
+    let display_lines = get_display_lines(area);
+    let view = TableView::from(&app.ui.view);
+    let columns = match view {
+        TableView::Files => parse_columns(&styler.config.columns.files, view),
+        _ => parse_columns(&styler.config.columns.units, view),
+    };
+
+    // widths come from every row so they do not jump around while scrolling
+    let cells: Vec<(usize, Vec<(String, Style)>)> = match &app.services {
+        Some((_, unit_files)) => {
+            if app.ui.view == View::ServiceUnits {
+                app.get_visible_units()
+                    .into_iter()
+                    .map(|u| {
+                        let row = columns
+                            .iter()
+                            .map(|column| styler.get_unit_cell(column, u))
+                            .collect();
+                        styler.mark_row(row, app.marked_units.contains(&u.name))
+                    })
                     .enumerate()
-                    .skip(scroll_offset)
-                    .take(display_lines)
-                    .map(|(idx, log)| styler.create_log_list_item(idx, app.ui.current_line, log))
                     .collect()
             } else {
-                vec![]
+                unit_files
+                    .iter()
+                    .map(|f| {
+                        let row = columns
+                            .iter()
+                            .map(|column| styler.get_file_cell(column, f))
+                            .collect();
+                        styler.mark_row(row, app.marked_units.contains(&f.name))
+                    })
+                    .enumerate()
+                    .collect()
             }
-        } else {
-            vec![]
-        };
+        }
+        None => vec![],
+    };
 
-        let final_items = if logs_items.is_empty() {
-            vec![ListItem::new("No log entries").style(priority_style)]
-        } else {
-            logs_items
-        };
+    let measured = measure_columns(
+        &columns,
+        &cells
+            .iter()
+            .map(|(_, row)| row.iter().map(|(text, _)| text.clone()).collect())
+            .collect::<Vec<Vec<String>>>(),
+    );
+    let widths = compute_widths(&columns, &measured, table_available_width(area, styler));
 
-        let logs_list = List::new(final_items)
-            .block(
-                Block::bordered()
-                    .title_alignment(Alignment::Center)
-                    .title(format!(
-                        "  {} -- {}/{}  ",
-                        app.selected_service.as_deref().unwrap_or("Logs"),
-                        priority,
-                        priority_str
-                    ))
-                    .style(priority_style),
-            )
-            .style(priority_style);
+    let scroll_offset = get_scroll_offset(
+        app.ui.services_viewport.get().offset,
+        current_line,
+        display_lines,
+        cells.len(),
+    );
+    app.ui.services_viewport.set(Viewport {
+        offset: scroll_offset,
+        height: display_lines,
+        first: cells.get(scroll_offset).map(|(idx, _)| *idx),
+        last: (scroll_offset + display_lines)
+            .min(cells.len())
+            .checked_sub(1)
+            .and_then(|last| cells.get(last))
+            .map(|(idx, _)| *idx),
+        h_scroll_limit: get_h_scroll_limit(&columns, &measured, &widths),
+    });
+
+    let header = styler.get_services_container(app.ui.view.clone(), &app.unit_filters);
+    let mut targets = app.ui.mouse_targets.borrow_mut();
+    targets.tabs = get_tab_areas(area, &header);
+    targets.services = Some(ClickArea::new(
+        get_table_body(area),
+        cells
+            .iter()
+            .skip(scroll_offset)
+            .take(display_lines)
+            .map(|(idx, _)| Some(*idx))
+            .collect(),
+    ));
+    drop(targets);
+
+    let table = build_table(
+        styler,
+        &columns,
+        cells,
+        (scroll_offset, display_lines),
+        (Some(current_line), app.ui.h_scroll),
+        &widths,
+        styler.get_column_titles(&columns, Some(app.get_sort())),
+    )
+    .block(
+        Block::bordered()
+            .title_alignment(Alignment::Center)
+            .title(header)
+            .border_style(styler.get_pane_border_style(!app.ui.is_in_logs)),
+    )
+    .style(
+        Style::default()
+            .fg(styler.config.get_palette_color("green"))
+            .add_modifier(Modifier::BOLD),
+    );
+
+    render_after_clear(frame, area, table);
+}
+
+fn draw_dashboard(frame: &mut Frame<'_>, area: Rect, app: &App, styler: &Styler) {
+    let header = styler.get_services_container(View::Dashboard, &app.unit_filters);
+    app.ui.mouse_targets.borrow_mut().tabs = get_tab_areas(area, &header);
+    let block = Block::bordered()
+        .title_alignment(Alignment::Center)
+        .title(header)
+        .border_style(styler.get_pane_border_style(!app.ui.is_in_logs));
+
+    let Some(dashboard) = &app.dashboard else {
+        let loading = Paragraph::new(" Loading dashboard...").block(block);
+        render_after_clear(frame, area, loading);
+        return;
+    };
+
+    let title_style = Style::default()
+        .fg(styler.config.get_palette_color("yellow"))
+        .add_modifier(Modifier::BOLD);
+    let label_style = Style::default()
+        .fg(styler.config.get_palette_color("white"))
+        .add_modifier(Modifier::BOLD);
+    let detail_style = Style::default().fg(styler.config.get_palette_color("gray"));
+    let cursor_width = styler.config.options.cursor_left.chars().count();
+    let label_width = dashboard
+        .get_entries()
+        .map(|entry| entry.label.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut lines: Vec<Line> = vec![];
+    let mut entry_lines = vec![];
+    let mut cursor_line = 0;
+    let mut idx = 0;
+
+    for section in &dashboard.sections {
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled(
+            format!(" {}", section.title),
+            title_style,
+        )));
+
+        if section.entries.is_empty() {
+            lines.push(Line::from(Span::styled("   none", detail_style)));
+        }
+
+        for entry in &section.entries {
+            let cursor = if idx == dashboard.current_line {
+                cursor_line = lines.len();
+                styler.config.options.cursor_left.clone()
+            } else {
+                " ".repeat(cursor_width)
+            };
+
+            entry_lines.push(lines.len());
+            lines.push(Line::from(vec![
+                Span::styled(
+                    cursor,
+                    Style::default().fg(styler.config.get_palette_color("blue")),
+                ),
+                Span::raw(" "),
+                Span::styled(
+                    format!("{:<width$}", entry.label, width = label_width),
+                    label_style,
+                ),
+                Span::raw("  "),
+                Span::styled(entry.detail.clone(), detail_style),
+            ]));
+            idx += 1;
+        }
+    }
+
+    if app.is_dashboard_loading() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(" Refreshing...", detail_style)));
+    }
+    if !dashboard.errors.is_empty() {
+        lines.push(Line::from(""));
+    }
+    for error in &dashboard.errors {
+        lines.push(Line::from(Span::styled(
+            format!(" {}", error),
+            Style::default().fg(styler.config.get_palette_color("red")),
+        )));
+    }
+
+    let display_lines = area.height.saturating_sub(2) as usize;
+    let scroll_offset = get_scroll_offset(0, cursor_line, display_lines, lines.len());
+    app.ui.mouse_targets.borrow_mut().dashboard = Some(ClickArea::new(
+        area.inner(Margin::new(1, 1)),
+        (scroll_offset..lines.len())
+            .take(display_lines)
+            .map(|line| entry_lines.iter().position(|entry| *entry == line))
+            .collect(),
+    ));
+    let dashboard = Paragraph::new(lines)
+        .block(block)
+        .scroll((scroll_offset as u16, 0));
+
+    render_after_clear(frame, area, dashboard);
+}
+
+pub fn draw_global_search(frame: &mut Frame<'_>, app: &App, styler: &Styler) -> Result<()> {
+    let area = center(
+        frame.area(),
+        Constraint::Percentage(90),
+        Constraint::Percentage(90),
+    );
+    let display_lines = area.height.saturating_sub(2) as usize;
+
+    let Some(search) = &app.global_search else {
+        return Ok(());
+    };
+
+    let scroll_offset = search
+        .current_line
+        .saturating_sub(display_lines.saturating_sub(1));
 
-        render_after_clear(frame, content_area, logs_list);
+    let items: Vec<ListItem> = if let Some(err) = &search.error {
+        vec![ListItem::new(format!(" {}", err))
+            .style(Style::default().fg(styler.config.get_palette_color("red")))]
+    } else if search.rows.is_empty() {
+        vec![ListItem::new(" No matches")]
     } else {
-        let mut services: Vec<ListItem> = match &app.services {
-            Some((units, unit_files)) => {
-                if app.ui.view == View::ServiceUnits {
-                    units
-                        .iter()
-                        .enumerate()
-                        .skip(scroll_offset)
-                        .take(display_lines)
-                        .map(|(idx, u)| styler.create_units_list_item(idx, app.ui.current_line, u))
-                        .collect()
-                } else {
-                    unit_files
-                        .iter()
-                        .enumerate()
-                        .skip(scroll_offset)
-                        .take(display_lines)
-                        .map(|(idx, f)| styler.create_files_list_item(idx, app.ui.current_line, f))
-                        .collect()
-                }
+        search
+            .rows
+            .iter()
+            .enumerate()
+            .skip(scroll_offset)
+            .take(display_lines)
+            .map(|(idx, row)| match row {
+                SearchRow::Group {
+                    unit,
+                    priority,
+                    count,
+                } => styler.create_search_group_item(
+                    idx,
+                    search.current_line,
+                    unit,
+                    *priority,
+                    *count,
+                ),
+                SearchRow::Entry(log) => styler.create_log_list_item(
+                    idx,
+                    search.current_line,
+                    log,
+                    find_bookmark(&app.bookmarks, &log.cursor),
+                ),
+            })
+            .collect()
+    };
+
+    let list = List::new(items).block(
+        Block::bordered()
+            .title_alignment(Alignment::Center)
+            .title(format!(
+                "  Search {:?} since {} -- {} matches  ",
+                search.query, search.since, search.matches
+            ))
+            .style(
+                Style::default()
+                    .fg(styler.config.get_palette_color("white"))
+                    .bg(styler.config.get_palette_color("black"))
+                    .add_modifier(Modifier::BOLD),
+            ),
+    );
+
+    render_after_clear(frame, area, list);
+    Ok(())
+}
+
+pub fn draw_fuzzy_finder(frame: &mut Frame<'_>, app: &App, styler: &Styler) -> Result<()> {
+    let area = center(
+        frame.area(),
+        Constraint::Max(100),
+        Constraint::Percentage(70),
+    );
+
+    let Some(finder) = &app.fuzzy_finder else {
+        return Ok(());
+    };
+
+    let [input_area, results_area] =
+        Layout::vertical([Constraint::Length(3), Constraint::Min(1)]).areas(area);
+    let display_lines = results_area.height.saturating_sub(2) as usize;
+    let scroll_offset = finder
+        .current_line
+        .saturating_sub(display_lines.saturating_sub(1));
+
+    let modal_style = Style::default()
+        .fg(styler.config.get_palette_color("white"))
+        .bg(styler.config.get_palette_color("black"))
+        .add_modifier(Modifier::BOLD);
+
+    let input = Paragraph::new(Line::from(vec![
+        Span::styled(
+            " > ",
+            Style::default().fg(styler.config.get_palette_color("blue")),
+        ),
+        Span::raw(finder.query.clone()),
+    ]))
+    .block(
+        Block::bordered()
+            .title(format!(
+                " Find service -- {}/{} ",
+                finder.results.len(),
+                finder.candidates.len()
+            ))
+            .style(modal_style),
+    );
+
+    let items: Vec<ListItem> = finder
+        .results
+        .iter()
+        .enumerate()
+        .skip(scroll_offset)
+        .take(display_lines)
+        .filter_map(|(idx, result)| {
+            finder.candidates.get(result.candidate).map(|candidate| {
+                styler.create_fuzzy_list_item(
+                    idx,
+                    finder.current_line,
+                    candidate,
+                    &result.positions,
+                )
+            })
+        })
+        .collect();
+
+    let results = List::new(items).block(Block::bordered().style(modal_style));
+
+    render_after_clear(frame, input_area, input);
+    render_after_clear(frame, results_area, results);
+    Ok(())
+}
+
+pub fn draw_filters_modal(frame: &mut Frame<'_>, app: &App, styler: &Styler) -> Result<()> {
+    let area = center(frame.area(), Constraint::Max(40), Constraint::Max(16));
+
+    let active = Style::default().fg(styler.config.get_palette_color("green"));
+    let inactive = Style::default().fg(styler.config.get_palette_color("gray"));
+
+    let mut lines: Vec<Line> = QUICK_FILTERS
+        .iter()
+        .filter_map(|(hotkey, filter)| {
+            let filter = UnitFilter::parse(filter)?;
+            let is_active = app.unit_filters.contains(&filter);
+
+            Some(Line::from(vec![
+                Span::styled(
+                    format!(" [{}] ", hotkey),
+                    Style::default().fg(styler.config.get_palette_color("yellow")),
+                ),
+                Span::styled(
+                    format!("{} {}", if is_active { "●" } else { "○" }, filter.label()),
+                    if is_active { active } else { inactive },
+                ),
+            ]))
+        })
+        .collect();
+
+    // filters from the config that have no hotkey
+    lines.extend(
+        app.unit_filters
+            .iter()
+            .filter(|filter| {
+                !QUICK_FILTERS
+                    .iter()
+                    .any(|(_, quick)| UnitFilter::parse(quick).as_ref() == Some(*filter))
+            })
+            .map(|filter| {
+                Line::from(vec![
+                    Span::raw("     "),
+                    Span::styled(format!("● {}", filter.label()), active),
+                ])
+            }),
+    );
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        " [c] clear  [F / Esc] close",
+        Style::default().fg(styler.config.get_palette_color("yellow")),
+    )));
+
+    let modal = Paragraph::new(lines).block(
+        Block::bordered().title(" Filter units ").style(
+            Style::default()
+                .fg(styler.config.get_palette_color("white"))
+                .bg(styler.config.get_palette_color("black"))
+                .add_modifier(Modifier::BOLD),
+        ),
+    );
+
+    render_after_clear(frame, area, modal);
+    Ok(())
+}
+
+pub fn draw_match_builder(frame: &mut Frame<'_>, app: &App, styler: &Styler) -> Result<()> {
+    let area = center(
+        frame.area(),
+        Constraint::Max(100),
+        Constraint::Percentage(70),
+    );
+
+    let Some(builder) = &app.match_builder else {
+        return Ok(());
+    };
+
+    let [fields_area, values_area] =
+        Layout::horizontal([Constraint::Length(26), Constraint::Min(1)]).areas(area);
+    let display_lines = values_area.height.saturating_sub(2) as usize;
+    let scroll_offset = builder
+        .current_line
+        .saturating_sub(display_lines.saturating_sub(1));
+
+    let modal_style = Style::default()
+        .fg(styler.config.get_palette_color("white"))
+        .bg(styler.config.get_palette_color("black"))
+        .add_modifier(Modifier::BOLD);
+    let active = Style::default().fg(styler.config.get_palette_color("green"));
+    let inactive = Style::default().fg(styler.config.get_palette_color("gray"));
+    let hint = Style::default().fg(styler.config.get_palette_color("yellow"));
+
+    let fields: Vec<Line> = MATCH_FIELDS
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| {
+            let count = app
+                .journal_matches
+                .iter()
+                .filter(|journal_match| journal_match.field == *field)
+                .count();
+            let label = if count > 0 {
+                format!(" {} ({})", field, count)
+            } else {
+                format!(" {}", field)
+            };
+
+            if idx == builder.field {
+                Line::from(Span::styled(
+                    label,
+                    Style::default()
+                        .fg(styler.config.get_palette_color("black"))
+                        .bg(styler.config.get_palette_color("blue")),
+                ))
+            } else {
+                Line::from(Span::styled(
+                    label,
+                    if count > 0 { active } else { inactive },
+                ))
             }
-            None => vec![],
-        };
+        })
+        .collect();
+
+    let values: Vec<Line> = match &builder.error {
+        Some(error) => vec![Line::from(Span::styled(
+            format!(" {}", error),
+            Style::default().fg(styler.config.get_palette_color("red")),
+        ))],
+        None if builder.is_loading() => vec![Line::from(Span::styled(" Loading...", inactive))],
+        None if builder.values.is_empty() => vec![Line::from(Span::styled(" No values", inactive))],
+        None => builder
+            .values
+            .iter()
+            .enumerate()
+            .skip(scroll_offset)
+            .take(display_lines)
+            .map(|(idx, value)| {
+                let is_active = app
+                    .journal_matches
+                    .contains(&JournalMatch::new(builder.get_field(), value));
+                let cursor = if idx == builder.current_line {
+                    styler.config.options.cursor_left.clone()
+                } else {
+                    " ".repeat(styler.config.options.cursor_left.chars().count())
+                };
+
+                Line::from(vec![
+                    Span::styled(cursor, hint),
+                    Span::styled(
+                        format!("{} {}", if is_active { "●" } else { "○" }, value),
+                        if is_active { active } else { inactive },
+                    ),
+                ])
+            })
+            .collect(),
+    };
+
+    let fields = Paragraph::new(fields).block(
+        Block::bordered()
+            .title(format!(" Matches -- {} ", app.journal_matches.len()))
+            .style(modal_style),
+    );
+    let values = Paragraph::new(values).block(
+        Block::bordered()
+            .title(format!(" journalctl -F {} ", builder.get_field()))
+            .title_bottom(Line::from(Span::styled(
+                " [h/l] field  [Enter] toggle  [c] clear  [= / Esc] apply ",
+                hint,
+            )))
+            .style(modal_style),
+    );
+
+    render_after_clear(frame, fields_area, fields);
+    render_after_clear(frame, values_area, values);
+    Ok(())
+}
+
+pub fn draw_patterns(frame: &mut Frame<'_>, app: &App, styler: &Styler) -> Result<()> {
+    let area = center(
+        frame.area(),
+        Constraint::Percentage(90),
+        Constraint::Percentage(90),
+    );
+    let display_lines = area.height.saturating_sub(2) as usize;
+
+    let Some(view) = &app.patterns else {
+        return Ok(());
+    };
 
-        services.insert(0, styler.get_column_titles(&app.ui.view));
+    let hint = Style::default().fg(styler.config.get_palette_color("yellow"));
 
-        let list = List::new(services)
-            .block(
-                Block::bordered()
-                    .title_alignment(Alignment::Center)
-                    .title(styler.get_services_container(app.ui.view.clone())),
+    let (title, footer, items): (String, &str, Vec<ListItem>) = match view.drill {
+        Some((pattern_idx, current_line)) => {
+            let pattern = &view.patterns[pattern_idx];
+            let scroll_offset = current_line.saturating_sub(display_lines.saturating_sub(1));
+
+            let items = app
+                .logs
+                .as_ref()
+                .and_then(|logs_arc| {
+                    let logs_map = logs_arc.lock().ok()?;
+                    let entries = logs_map.get(&app.ui.selected_priority?)?;
+                    Some(
+                        pattern
+                            .lines
+                            .iter()
+                            .enumerate()
+                            .skip(scroll_offset)
+                            .take(display_lines)
+                            .filter_map(|(idx, line)| {
+                                entries.get(*line).map(|log| {
+                                    styler.create_log_list_item(
+                                        idx,
+                                        current_line,
+                                        log,
+                                        find_bookmark(&app.bookmarks, &log.cursor),
+                                    )
+                                })
+                            })
+                            .collect(),
+                    )
+                })
+                .unwrap_or_default();
+
+            (
+                format!("  {} -- {} entries  ", pattern.template, pattern.count),
+                " [Enter] go to entry  [h / Esc] back ",
+                items,
+            )
+        }
+        None => {
+            let scroll_offset = view
+                .current_line
+                .saturating_sub(display_lines.saturating_sub(1));
+
+            let items = if view.patterns.is_empty() {
+                vec![ListItem::new(" No entries")]
+            } else {
+                view.patterns
+                    .iter()
+                    .enumerate()
+                    .skip(scroll_offset)
+                    .take(display_lines)
+                    .map(|(idx, pattern)| {
+                        styler.create_pattern_item(idx, view.current_line, pattern)
+                    })
+                    .collect()
+            };
+
+            (
+                format!("  Patterns -- {}  ", view.patterns.len()),
+                " [Enter] entries  [P / Esc] close ",
+                items,
             )
+        }
+    };
+
+    let list = List::new(items).block(
+        Block::bordered()
+            .title_alignment(Alignment::Center)
+            .title(title)
+            .title_bottom(Line::from(Span::styled(footer, hint)))
             .style(
                 Style::default()
-                    .fg(styler.config.get_palette_color("green"))
+                    .fg(styler.config.get_palette_color("white"))
+                    .bg(styler.config.get_palette_color("black"))
                     .add_modifier(Modifier::BOLD),
-            );
-
-        render_after_clear(frame, content_area, list);
-    }
-
-    let bottom_area = styler.get_bottom_info(&app.ui);
-    render_after_clear(frame, action_area, bottom_area);
+            ),
+    );
 
+    render_after_clear(frame, area, list);
     Ok(())
 }
 
-pub fn draw_help_modal(frame: &mut Frame<'_>, styler: &Styler) -> Result<()> {
-    let area = center(frame.area(), Constraint::Max(40), Constraint::Max(20));
+pub fn draw_bookmarks(frame: &mut Frame<'_>, app: &App, styler: &Styler) -> Result<()> {
+    let area = center(
+        frame.area(),
+        Constraint::Percentage(90),
+        Constraint::Percentage(90),
+    );
+    let display_lines = area.height.saturating_sub(2) as usize;
+    let current_line = app.ui.bookmarks_line;
+    let scroll_offset = current_line.saturating_sub(display_lines.saturating_sub(1));
 
-    let help_modal = Paragraph::new(HELP)
-        .block(
-            Block::bordered().title(" Help ").style(
+    let items: Vec<ListItem> = if app.bookmarks.is_empty() {
+        vec![ListItem::new(" No bookmarks")]
+    } else {
+        app.bookmarks
+            .iter()
+            .enumerate()
+            .skip(scroll_offset)
+            .take(display_lines)
+            .map(|(idx, bookmark)| styler.create_bookmark_item(idx, current_line, bookmark))
+            .collect()
+    };
+
+    let hint = Style::default().fg(styler.config.get_palette_color("yellow"));
+    let list = List::new(items).block(
+        Block::bordered()
+            .title_alignment(Alignment::Center)
+            .title(format!("  Bookmarks -- {}  ", app.bookmarks.len()))
+            .title_bottom(Line::from(Span::styled(
+                " [Enter] open  [a] note  [d] delete  [B / Esc] close ",
+                hint,
+            )))
+            .style(
                 Style::default()
                     .fg(styler.config.get_palette_color("white"))
                     .bg(styler.config.get_palette_color("black"))
                     .add_modifier(Modifier::BOLD),
             ),
-        )
-        .alignment(Alignment::Center);
+    );
 
-    render_after_clear(frame, area, help_modal);
+    render_after_clear(frame, area, list);
+    Ok(())
+}
+
+// generated from the keymap, split into columns when it is taller than the screen
+pub fn draw_help_modal(frame: &mut Frame<'_>, styler: &Styler) -> Result<()> {
+    let mut lines = styler.config.keymap.get_help_lines();
+    lines.push("Exit search mode: [Esc]".to_string());
+
+    let max_rows = (frame.area().height.saturating_sub(6) as usize).max(1);
+    let columns = lines.len().div_ceil(max_rows);
+    let rows = lines.len().div_ceil(columns);
+    let column_width = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0) as u16
+        + 2;
+
+    let area = center(
+        frame.area(),
+        Constraint::Max(column_width * columns as u16 + 2),
+        Constraint::Max(rows as u16 + 4),
+    );
+
+    let block = Block::bordered().title(" Help ").style(
+        Style::default()
+            .fg(styler.config.get_palette_color("white"))
+            .bg(styler.config.get_palette_color("black"))
+            .add_modifier(Modifier::BOLD),
+    );
+    let inner = block.inner(area);
+    render_after_clear(frame, area, block);
+
+    let [title_area, columns_area] =
+        Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).areas(inner);
+    frame.render_widget(
+        Paragraph::new("Rounal - Key Mappings").alignment(Alignment::Center),
+        title_area,
+    );
+
+    let column_areas = Layout::horizontal(vec![Constraint::Fill(1); columns]).split(columns_area);
+    for (chunk, column_area) in lines.chunks(rows).zip(column_areas.iter()) {
+        let column: Vec<Line> = chunk.iter().map(|line| Line::from(line.as_str())).collect();
+        frame.render_widget(Paragraph::new(column), column_area.inner(Margin::new(1, 0)));
+    }
 
     Ok(())
 }
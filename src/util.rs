@@ -4,7 +4,9 @@ pub const DOCS: &str = r#"
 Commands that the program will run.
 systemctl list-units --type=service --all,
 systemctl list-unit-files --type=service --all,
-sudo journalctl --unit=<service> --reverse --priority=<1-7>
+sudo journalctl --unit=<service> --reverse --priority=<1-7> --output=json,
+sudo journalctl --unit=<service> --reverse --priority=<1-7> --lines=200 --output=json,
+sudo journalctl --grep=<pattern> --since=<window> --reverse --output=json
 
 Short description of service fields:
 LOAD:
@@ -19,33 +21,43 @@ PRESET:
     System default recommendation for enabling services.
 "#;
 
-pub const HELP: &str = "Rounal - Key Mappings\n\n\
-        Move: [hjkl / arrow keys]\n\
-        Select: [Enter]\n\
-        Close logs: [c]\n\
-        Change priority: [1-7] or [Move]\n\
-        Toggle see line in a modal: [K]\n\
-        Yank message: [y] \n\
-        Begin search: [/] \n\
-        Toddle read explanations: [E] \n\
-        Exit search mode: [Esc] \n\
-        Quit: [q / Esc]\n\
-        Toggle Help: [?]\n";
+// unit names can hold spaces and slashes
+pub fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || "-_.@".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
 
-pub trait PadStr {
-    fn pad_with(&self, width: usize) -> String;
+pub fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{}/{}", home, rest),
+        _ => path.to_string(),
+    }
 }
 
-impl PadStr for str {
-    fn pad_with(&self, width: usize) -> String {
-        format!("{:<width$}", self, width = width)
+// The content goes to a file next to the path that is then renamed over it,
+// so a crash leaves the old file or the new one, never a truncated one.
+pub async fn write_file_atomic(path: &str, content: &str) -> std::io::Result<()> {
+    if let Some(parent) = std::path::Path::new(path).parent() {
+        tokio::fs::create_dir_all(parent).await?;
     }
+    let temp = format!("{}.tmp", path);
+    tokio::fs::write(&temp, content).await?;
+    tokio::fs::rename(&temp, path).await
 }
 
+// the syslog levels of PRIORITY, a bucket of -p N is named after its N
 pub fn map_to_priority_str(priority: &u8) -> &'static str {
     match priority {
-        1 => "emerg",
-        2 => "alert",
+        0 => "emerg",
+        1 => "alert",
+        2 => "crit",
         3 => "err",
         4 => "warn",
         5 => "notice",
@@ -99,3 +111,17 @@ pub fn get_preset_color_str(preset: &Preset) -> &'static str {
         _ => "white",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_syslog_priorities() {
+        assert_eq!(map_to_priority_str(&0), "emerg");
+        assert_eq!(map_to_priority_str(&2), "crit");
+        assert_eq!(map_to_priority_str(&3), "err");
+        assert_eq!(map_to_priority_str(&7), "debug");
+        assert_eq!(map_to_priority_str(&8), "unknown");
+    }
+}